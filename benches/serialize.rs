@@ -1,7 +1,8 @@
 use std::time::Duration;
 
 use criterion::{criterion_group, criterion_main, Criterion};
-use nyoom_json::{JsonBuffer, UnescapedStr, WriteToJson};
+use nyoom_json::{template, JsonBuffer, UnescapedStr, WriteToJsonRef};
+use nyoom_json_derive::WriteToJson;
 use serde::{Deserialize, Serialize};
 
 macro_rules! noescape {
@@ -17,8 +18,9 @@ macro_rules! ser_arr {
         }
     };
 }
-#[derive(Deserialize, Serialize, Clone)]
+#[derive(Deserialize, Serialize, Clone, WriteToJson)]
 #[serde(rename_all = "camelCase")]
+#[nyoom(rename_all = "camelCase")]
 pub struct Root {
     license: RootLicense,
     repository: String,
@@ -71,19 +73,84 @@ pub fn write_root<S: JsonBuffer>(root: &Root, out: &mut nyoom_json::Serializer<S
     }
 }
 
-#[derive(Deserialize, Serialize, Clone)]
+template! {
+    pub struct RootTemplate<'a> = {
+        "license": { "name": (license_name: &'a str), "url": (license_url: &'a str) },
+        "repository": (repository: &'a str),
+        "lastUpdate": (last_update: &'a str),
+        "data": (data: AnimeTemplates<'a>)
+    }
+}
+
+template! {
+    pub struct AnimeTemplate<'a> = {
+        "sources": (sources: &'a [String]),
+        "title": (title: &'a str),
+        "type": (ty: AnimeType),
+        "episodes": (episodes: i64),
+        "status": (status: AnimeStatus),
+        "animeSeason": { "season": (season: AnimeSeasons), "year": (year: Option<i64>) },
+        "picture": (picture: &'a str),
+        "thumbnail": (thumbnail: &'a str),
+        "synonyms": (synonyms: &'a [String]),
+        "relations": (relations: &'a [String]),
+        "tags": (tags: &'a [String])
+    }
+}
+
+pub struct AnimeTemplates<'a>(&'a [Anime]);
+
+impl<S: JsonBuffer> WriteToJsonRef<S> for AnimeTemplates<'_> {
+    #[inline(always)]
+    fn write_to_json_ref(&self, out: &mut S) {
+        let mut ser = nyoom_json::Serializer::new(out);
+        let mut arr = ser.array();
+        for series in self.0 {
+            arr.add(AnimeTemplate {
+                sources: &series.sources,
+                title: &series.title,
+                ty: series.ty,
+                episodes: series.episodes,
+                status: series.status,
+                season: series.anime_season.season,
+                year: series.anime_season.year,
+                picture: &series.picture,
+                thumbnail: &series.thumbnail,
+                synonyms: &series.synonyms,
+                relations: &series.relations,
+                tags: &series.tags,
+            });
+        }
+    }
+}
+
+#[inline(always)]
+pub fn write_root_template<S: JsonBuffer>(root: &Root, out: &mut nyoom_json::Serializer<S>) {
+    out.write(RootTemplate {
+        license_name: &root.license.name,
+        license_url: &root.license.url,
+        repository: &root.repository,
+        last_update: &root.last_update,
+        data: AnimeTemplates(&root.data),
+    });
+}
+
+#[derive(Deserialize, Serialize, Clone, WriteToJson)]
 #[serde(rename_all = "camelCase")]
+#[nyoom(rename_all = "camelCase")]
 pub struct RootLicense {
     name: String,
     url: String,
 }
 
-#[derive(Deserialize, Serialize, Clone)]
+#[derive(Deserialize, Serialize, Clone, WriteToJson)]
 #[serde(rename_all = "camelCase")]
+#[nyoom(rename_all = "camelCase")]
 pub struct Anime {
     sources: Vec<String>,
     title: String,
     #[serde(rename = "type")]
+    #[nyoom(rename = "type")]
     ty: AnimeType,
     episodes: i64,
     status: AnimeStatus,
@@ -95,9 +162,9 @@ pub struct Anime {
     tags: Vec<String>,
 }
 
-#[derive(Copy, Clone, Deserialize, Serialize, strum::EnumString, strum::AsRefStr)]
-#[strum(serialize_all = "UPPERCASE")]
+#[derive(Copy, Clone, Deserialize, Serialize, WriteToJson)]
 #[serde(rename_all = "UPPERCASE")]
+#[nyoom(rename_all = "UPPERCASE")]
 pub enum AnimeType {
     Tv,
     Movie,
@@ -107,15 +174,9 @@ pub enum AnimeType {
     Unknown,
 }
 
-impl<S: JsonBuffer> WriteToJson<S> for AnimeType {
-    fn write_to_json(self, out: &mut S) {
-        UnescapedStr::create(self.as_ref()).write_to_json(out)
-    }
-}
-
-#[derive(Copy, Clone, Deserialize, Serialize, strum::EnumString, strum::AsRefStr)]
-#[strum(serialize_all = "UPPERCASE")]
+#[derive(Copy, Clone, Deserialize, Serialize, WriteToJson)]
 #[serde(rename_all = "UPPERCASE")]
+#[nyoom(rename_all = "UPPERCASE")]
 pub enum AnimeStatus {
     Finished,
     Ongoing,
@@ -123,21 +184,15 @@ pub enum AnimeStatus {
     Unknown,
 }
 
-impl<S: JsonBuffer> WriteToJson<S> for AnimeStatus {
-    fn write_to_json(self, out: &mut S) {
-        UnescapedStr::create(self.as_ref()).write_to_json(out)
-    }
-}
-
-#[derive(Deserialize, Serialize, Clone)]
+#[derive(Deserialize, Serialize, Clone, WriteToJson)]
 pub struct AnimeSeason {
     season: AnimeSeasons,
     year: Option<i64>,
 }
 
-#[derive(Copy, Clone, Deserialize, Serialize, strum::EnumString, strum::AsRefStr)]
-#[strum(serialize_all = "UPPERCASE")]
+#[derive(Copy, Clone, Deserialize, Serialize, WriteToJson)]
 #[serde(rename_all = "UPPERCASE")]
+#[nyoom(rename_all = "UPPERCASE")]
 pub enum AnimeSeasons {
     Spring,
     Summer,
@@ -146,12 +201,6 @@ pub enum AnimeSeasons {
     Undefined,
 }
 
-impl<S: JsonBuffer> WriteToJson<S> for AnimeSeasons {
-    fn write_to_json(self, out: &mut S) {
-        UnescapedStr::create(self.as_ref()).write_to_json(out)
-    }
-}
-
 pub fn criterion_benchmark(c: &mut Criterion) {
     let mut anime: Root = serde_json::from_str(include_str!("anime-test.json")).unwrap();
     anime.data.truncate(200);
@@ -164,7 +213,7 @@ pub fn criterion_benchmark(c: &mut Criterion) {
     group.bench_function("serde", |b| {
         b.iter_with_large_drop(|| {
             let mut out = Vec::with_capacity(size);
-            serde_json::to_writer(&mut out, &anime);
+            serde_json::to_writer(&mut out, &anime).unwrap();
             out
         })
     });
@@ -176,6 +225,22 @@ pub fn criterion_benchmark(c: &mut Criterion) {
             out
         });
     });
+    group.bench_function("nyoom (derive)", |b| {
+        b.iter_with_large_drop(|| {
+            let mut out = String::with_capacity(size);
+            let mut ser = nyoom_json::Serializer::new(&mut out);
+            ser.write(&anime);
+            out
+        });
+    });
+    group.bench_function("nyoom (template)", |b| {
+        b.iter_with_large_drop(|| {
+            let mut out = String::with_capacity(size);
+            let mut ser = nyoom_json::Serializer::new(&mut out);
+            write_root_template(&anime, &mut ser);
+            out
+        });
+    });
 }
 
 criterion_group!(benches, criterion_benchmark);
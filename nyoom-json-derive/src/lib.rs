@@ -0,0 +1,533 @@
+//! `#[derive(WriteToJson)]` for [nyoom-json](https://docs.rs/nyoom-json). Use it through nyoom-json's `derive` feature rather than depending on this crate directly.
+
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote, quote_spanned};
+use syn::{
+    parse_macro_input, parse_quote, spanned::Spanned, Data, DataEnum, DeriveInput, Error, ExprPath,
+    Fields, GenericParam, Ident, LitStr, Result, Variant,
+};
+
+/// Derives `WriteToJson` and `WriteToJsonRef` for a struct or enum, and `WriteFields` for structs with named fields.
+///
+/// Structs with named fields are written as objects, newtype structs as their inner value, tuple structs as arrays and unit structs as `null`.
+/// Unit variants are written as strings, and any other variant as an object with the variant's name as its only key, the same way serde does it.
+///
+/// Every key is rendered at compile time, so writing one is a single `push_str`. Keys that would need escaping are rejected.
+///
+/// # Attributes
+///
+/// On the struct or enum:
+/// - `#[nyoom(rename_all = "...")]` renames every field (or variant) to `lowercase`, `UPPERCASE`, `PascalCase`, `camelCase`, `snake_case`, `SCREAMING_SNAKE_CASE`, `kebab-case` or `SCREAMING-KEBAB-CASE`.
+///
+/// On a variant:
+/// - `#[nyoom(rename = "...")]` writes the variant under a different name.
+/// - `#[nyoom(rename_all = "...")]` renames the fields of a struct variant.
+///
+/// On a field:
+/// - `#[nyoom(rename = "...")]` writes the field under a different key.
+/// - `#[nyoom(skip)]` leaves the field out.
+/// - `#[nyoom(skip_if = "path")]` leaves the field out when `path(&field)` returns `true`.
+/// - `#[nyoom(flatten)]` writes the field's own fields into this object instead of nesting it. The field must implement `WriteFields`.
+///
+/// # Examples
+/// ```
+/// use nyoom_json::{Serializer, WriteToJson};
+///
+/// #[derive(WriteToJson)]
+/// #[nyoom(rename_all = "camelCase")]
+/// struct Cat {
+///     name: &'static str,
+///     meow_decibels: u8,
+///     #[nyoom(skip_if = "Option::is_none")]
+///     illness: Option<&'static str>,
+///     #[nyoom(flatten)]
+///     owner: Owner,
+///     #[nyoom(rename = "type")]
+///     kind: Kind,
+/// }
+///
+/// #[derive(WriteToJson)]
+/// struct Owner {
+///     owner_name: &'static str,
+/// }
+///
+/// #[derive(Clone, Copy, WriteToJson)]
+/// #[nyoom(rename_all = "SCREAMING_SNAKE_CASE")]
+/// enum Kind {
+///     HouseCat,
+///     Lion,
+/// }
+///
+/// let cat = Cat {
+///     name: "mittens",
+///     meow_decibels: 45,
+///     illness: None,
+///     owner: Owner { owner_name: "emily" },
+///     kind: Kind::HouseCat,
+/// };
+///
+/// let mut out = String::new();
+/// let mut ser = Serializer::new(&mut out);
+/// ser.write(&cat);
+///
+/// assert_eq!(
+///     out,
+///     r#"{"name":"mittens","meowDecibels":45,"owner_name":"emily","type":"HOUSE_CAT"}"#
+/// );
+/// ```
+///
+/// ```
+/// use nyoom_json::{Serializer, WriteToJson};
+///
+/// #[derive(WriteToJson)]
+/// enum Toy {
+///     Mouse,
+///     Ball(f32),
+///     String(u32, bool),
+///     Box { width: u32, height: u32 },
+/// }
+///
+/// let mut out = String::new();
+/// let mut ser = Serializer::new(&mut out);
+/// ser.write([
+///     Toy::Mouse,
+///     Toy::Ball(2.5),
+///     Toy::String(30, true),
+///     Toy::Box { width: 40, height: 20 },
+/// ]);
+///
+/// assert_eq!(
+///     out,
+///     r#"["Mouse",{"Ball":2.5},{"String":[30,true]},{"Box":{"width":40,"height":20}}]"#
+/// );
+/// ```
+///
+/// ```compile_fail
+/// #[derive(nyoom_json::WriteToJson)]
+/// struct Cat {
+///     #[nyoom(rename = "needs\"escaping")]
+///     name: String,
+/// }
+/// ```
+#[proc_macro_derive(WriteToJson, attributes(nyoom))]
+pub fn derive_write_to_json(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn expand(input: DeriveInput) -> Result<TokenStream> {
+    let container = Attrs::parse(&input.attrs)?;
+    container.only(&["rename_all"])?;
+
+    let ident = &input.ident;
+    let mut generics = input.generics.clone();
+    let type_params: Vec<Ident> = generics.type_params().map(|p| p.ident.clone()).collect();
+    {
+        let where_clause = generics.make_where_clause();
+        for param in type_params {
+            where_clause
+                .predicates
+                .push(parse_quote!(#param: ::nyoom_json::WriteToJsonRef<__S>));
+        }
+    }
+    let (_, ty_generics, where_clause) = generics.split_for_impl();
+
+    let mut impl_generics = generics.clone();
+    impl_generics.params.push(GenericParam::Type(
+        parse_quote!(__S: ::nyoom_json::JsonBuffer),
+    ));
+    let (impl_generics, _, _) = impl_generics.split_for_impl();
+
+    let write_ref = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(_) => quote! {
+                let mut obj = ::nyoom_json::SingleValueSerializer::new(out).object();
+                ::nyoom_json::WriteFields::write_fields(self, &mut obj);
+            },
+            Fields::Unnamed(_) => {
+                let accessors = data.fields.members().map(|m| quote!(&self.#m)).collect();
+                match unnamed(&data.fields, accessors)? {
+                    Unnamed::Single(access) => {
+                        quote!(::nyoom_json::WriteToJsonRef::write_to_json_ref(#access, out);)
+                    }
+                    Unnamed::Array(kept) => quote! {
+                        let mut arr = ::nyoom_json::SingleValueSerializer::new(out).array();
+                        #(arr.add(#kept);)*
+                    },
+                }
+            }
+            Fields::Unit => quote!(::nyoom_json::JsonBuffer::push_str(out, "null");),
+        },
+        Data::Enum(data) => write_enum(data, container.rename_all)?,
+        Data::Union(_) => {
+            return Err(Error::new(
+                Span::call_site(),
+                "WriteToJson can't be derived for unions",
+            ))
+        }
+    };
+
+    let write_fields = match &input.data {
+        Data::Struct(data) if matches!(data.fields, Fields::Named(_)) => {
+            let accessors = data.fields.members().map(|m| quote!(&self.#m)).collect();
+            let body = write_named(&data.fields, accessors, container.rename_all, quote!(obj))?;
+            let obj = match body.is_empty() {
+                true => quote!(_obj),
+                false => quote!(obj),
+            };
+            quote! {
+                impl #impl_generics ::nyoom_json::WriteFields<__S> for #ident #ty_generics #where_clause {
+                    #[inline]
+                    fn write_fields(&self, #obj: &mut ::nyoom_json::ObjectWriter<'_, __S>) {
+                        #body
+                    }
+                }
+            }
+        }
+        _ => quote!(),
+    };
+
+    Ok(quote! {
+        #write_fields
+
+        impl #impl_generics ::nyoom_json::WriteToJsonRef<__S> for #ident #ty_generics #where_clause {
+            #[inline]
+            fn write_to_json_ref(&self, out: &mut __S) {
+                #write_ref
+            }
+        }
+
+        impl #impl_generics ::nyoom_json::WriteToJson<__S> for #ident #ty_generics #where_clause {
+            #[inline(always)]
+            fn write_to_json(self, out: &mut __S) {
+                ::nyoom_json::WriteToJsonRef::write_to_json_ref(&self, out)
+            }
+        }
+    })
+}
+
+/// Writes named fields into the object writer `obj`, reading each field through the matching expression in `accessors`.
+fn write_named(
+    fields: &Fields,
+    accessors: Vec<TokenStream>,
+    rename_all: Option<RenameRule>,
+    obj: TokenStream,
+) -> Result<TokenStream> {
+    let mut body = TokenStream::new();
+    for (field, access) in fields.iter().zip(accessors) {
+        let attrs = Attrs::parse(&field.attrs)?;
+        attrs.only(&["rename", "skip", "skip_if", "flatten"])?;
+        if attrs.skip {
+            continue;
+        }
+
+        if attrs.flatten {
+            if attrs.rename.is_some() || attrs.skip_if.is_some() {
+                return Err(Error::new(
+                    field.span(),
+                    "flatten can't be combined with rename or skip_if",
+                ));
+            }
+
+            body.extend(quote_spanned!(field.ty.span()=> #obj.flatten(#access);));
+            continue;
+        }
+
+        let name = field.ident.as_ref().map(unraw).unwrap_or_default();
+        let key = key(
+            &attrs.rename,
+            rename_all.map_or(name.clone(), |r| r.apply_to_field(&name)),
+        )?;
+        let write = quote!(#obj.field(::nyoom_json::key!(#key), #access););
+        body.extend(match attrs.skip_if {
+            Some(path) => quote!(if !#path(#access) { #write }),
+            None => write,
+        });
+    }
+
+    Ok(body)
+}
+
+enum Unnamed {
+    Single(TokenStream),
+    Array(Vec<TokenStream>),
+}
+
+/// Tuple fields are written as-is when there's only one of them, and as an array otherwise.
+fn unnamed(fields: &Fields, accessors: Vec<TokenStream>) -> Result<Unnamed> {
+    let mut kept = Vec::new();
+    for (field, access) in fields.iter().zip(accessors) {
+        let attrs = Attrs::parse(&field.attrs)?;
+        attrs.only(&["skip"])?;
+        if !attrs.skip {
+            kept.push(access);
+        }
+    }
+
+    Ok(match fields.len() {
+        1 if kept.len() == 1 => Unnamed::Single(kept.remove(0)),
+        _ => Unnamed::Array(kept),
+    })
+}
+
+fn write_enum(data: &DataEnum, rename_all: Option<RenameRule>) -> Result<TokenStream> {
+    let mut arms = TokenStream::new();
+    for variant in &data.variants {
+        let attrs = Attrs::parse(&variant.attrs)?;
+        attrs.only(&["rename", "rename_all"])?;
+
+        let ident = &variant.ident;
+        let name = key(
+            &attrs.rename,
+            rename_all.map_or(unraw(ident), |r| r.apply_to_variant(&unraw(ident))),
+        )?;
+        let bindings: Vec<Ident> = (0..variant.fields.len())
+            .map(|i| format_ident!("__field{}", i))
+            .collect();
+        let pattern = pattern(variant, &bindings)?;
+        let accessors = bindings.iter().map(|b| quote!(#b)).collect();
+
+        let write = match &variant.fields {
+            Fields::Unit => {
+                let quoted = format!("\"{}\"", name.value());
+                quote!(::nyoom_json::JsonBuffer::push_str(out, #quoted);)
+            }
+            Fields::Named(_) => {
+                let body =
+                    write_named(&variant.fields, accessors, attrs.rename_all, quote!(inner))?;
+                let inner = match body.is_empty() {
+                    true => quote!(_inner),
+                    false => quote!(mut inner),
+                };
+                quote! {
+                    let mut obj = ::nyoom_json::SingleValueSerializer::new(out).object();
+                    let #inner = obj.object_field(::nyoom_json::key!(#name));
+                    #body
+                }
+            }
+            Fields::Unnamed(_) => {
+                let field = match unnamed(&variant.fields, accessors)? {
+                    Unnamed::Single(access) => {
+                        quote!(obj.field(::nyoom_json::key!(#name), #access);)
+                    }
+                    Unnamed::Array(kept) => quote! {
+                        let mut arr = obj.array_field(::nyoom_json::key!(#name));
+                        #(arr.add(#kept);)*
+                    },
+                };
+                quote! {
+                    let mut obj = ::nyoom_json::SingleValueSerializer::new(out).object();
+                    #field
+                }
+            }
+        };
+
+        arms.extend(quote!(#pattern => { #write }));
+    }
+
+    Ok(match data.variants.is_empty() {
+        true => quote!(match *self {}),
+        false => quote! {
+            match self {
+                #arms
+            }
+        },
+    })
+}
+
+/// A pattern matching `variant` that binds each field that isn't skipped to its binding.
+fn pattern(variant: &Variant, bindings: &[Ident]) -> Result<TokenStream> {
+    let ident = &variant.ident;
+    let mut binds = Vec::new();
+    for (field, binding) in variant.fields.iter().zip(bindings) {
+        let bind = match Attrs::parse(&field.attrs)?.skip {
+            true => quote!(_),
+            false => quote!(#binding),
+        };
+        binds.push(match &field.ident {
+            Some(name) => quote!(#name: #bind),
+            None => bind,
+        });
+    }
+
+    Ok(match &variant.fields {
+        Fields::Named(_) => quote!(Self::#ident { #(#binds),* }),
+        Fields::Unnamed(_) => quote!(Self::#ident(#(#binds),*)),
+        Fields::Unit => quote!(Self::#ident),
+    })
+}
+
+/// The key a field or variant is written under, checked so it can be rendered without escaping.
+fn key(rename: &Option<LitStr>, default: String) -> Result<LitStr> {
+    let lit = match rename {
+        Some(lit) => lit.clone(),
+        None => LitStr::new(&default, Span::call_site()),
+    };
+
+    match lit
+        .value()
+        .bytes()
+        .any(|b| b < 0x20 || b == b'"' || b == b'\\')
+    {
+        true => Err(Error::new(
+            lit.span(),
+            "key contains characters that need to be escaped!",
+        )),
+        false => Ok(lit),
+    }
+}
+
+fn unraw(ident: &Ident) -> String {
+    let name = ident.to_string();
+    match name.strip_prefix("r#") {
+        Some(name) => name.to_owned(),
+        None => name,
+    }
+}
+
+#[derive(Default)]
+struct Attrs {
+    rename: Option<LitStr>,
+    rename_all: Option<RenameRule>,
+    skip: bool,
+    skip_if: Option<ExprPath>,
+    flatten: bool,
+    seen: Vec<(&'static str, Span)>,
+}
+
+impl Attrs {
+    fn parse(attrs: &[syn::Attribute]) -> Result<Attrs> {
+        let mut out = Attrs::default();
+        for attr in attrs.iter().filter(|a| a.path().is_ident("nyoom")) {
+            attr.parse_nested_meta(|meta| {
+                let span = meta.path.span();
+                let name = if meta.path.is_ident("rename") {
+                    out.rename = Some(meta.value()?.parse()?);
+                    "rename"
+                } else if meta.path.is_ident("rename_all") {
+                    let lit: LitStr = meta.value()?.parse()?;
+                    out.rename_all = Some(RenameRule::parse(&lit)?);
+                    "rename_all"
+                } else if meta.path.is_ident("skip") {
+                    out.skip = true;
+                    "skip"
+                } else if meta.path.is_ident("skip_if") {
+                    let lit: LitStr = meta.value()?.parse()?;
+                    out.skip_if = Some(lit.parse()?);
+                    "skip_if"
+                } else if meta.path.is_ident("flatten") {
+                    out.flatten = true;
+                    "flatten"
+                } else {
+                    return Err(meta.error("unknown nyoom attribute"));
+                };
+
+                out.seen.push((name, span));
+                Ok(())
+            })?;
+        }
+
+        Ok(out)
+    }
+
+    /// Errors on any attribute that isn't in `allowed`.
+    fn only(&self, allowed: &[&str]) -> Result<()> {
+        match self.seen.iter().find(|(name, _)| !allowed.contains(name)) {
+            Some((name, span)) => Err(Error::new(
+                *span,
+                format!("`{}` isn't supported here", name),
+            )),
+            None => Ok(()),
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum RenameRule {
+    Lower,
+    Upper,
+    Pascal,
+    Camel,
+    Snake,
+    ScreamingSnake,
+    Kebab,
+    ScreamingKebab,
+}
+
+impl RenameRule {
+    fn parse(lit: &LitStr) -> Result<RenameRule> {
+        Ok(match lit.value().as_str() {
+            "lowercase" => RenameRule::Lower,
+            "UPPERCASE" => RenameRule::Upper,
+            "PascalCase" => RenameRule::Pascal,
+            "camelCase" => RenameRule::Camel,
+            "snake_case" => RenameRule::Snake,
+            "SCREAMING_SNAKE_CASE" => RenameRule::ScreamingSnake,
+            "kebab-case" => RenameRule::Kebab,
+            "SCREAMING-KEBAB-CASE" => RenameRule::ScreamingKebab,
+            _ => return Err(Error::new(lit.span(), "unknown rename_all rule")),
+        })
+    }
+
+    /// Renames a `snake_case` field.
+    fn apply_to_field(self, field: &str) -> String {
+        match self {
+            RenameRule::Lower | RenameRule::Snake => field.to_owned(),
+            RenameRule::Upper | RenameRule::ScreamingSnake => field.to_ascii_uppercase(),
+            RenameRule::Pascal | RenameRule::Camel => {
+                let mut out = String::new();
+                let mut capitalize = matches!(self, RenameRule::Pascal);
+                for c in field.chars() {
+                    match c {
+                        '_' if !out.is_empty() => capitalize = true,
+                        c if capitalize => {
+                            out.push(c.to_ascii_uppercase());
+                            capitalize = false;
+                        }
+                        c => out.push(c),
+                    }
+                }
+                out
+            }
+            RenameRule::Kebab => field.replace('_', "-"),
+            RenameRule::ScreamingKebab => field.to_ascii_uppercase().replace('_', "-"),
+        }
+    }
+
+    /// Renames a `PascalCase` variant.
+    fn apply_to_variant(self, variant: &str) -> String {
+        match self {
+            RenameRule::Pascal => variant.to_owned(),
+            RenameRule::Lower => variant.to_ascii_lowercase(),
+            RenameRule::Upper => variant.to_ascii_uppercase(),
+            RenameRule::Camel => {
+                let mut chars = variant.chars();
+                chars
+                    .next()
+                    .map(|c| c.to_ascii_lowercase().to_string() + chars.as_str())
+                    .unwrap_or_default()
+            }
+            RenameRule::Snake => {
+                let mut out = String::new();
+                for (i, c) in variant.char_indices() {
+                    if i > 0 && c.is_uppercase() {
+                        out.push('_');
+                    }
+                    out.push(c.to_ascii_lowercase());
+                }
+                out
+            }
+            RenameRule::ScreamingSnake => RenameRule::Snake
+                .apply_to_variant(variant)
+                .to_ascii_uppercase(),
+            RenameRule::Kebab => RenameRule::Snake
+                .apply_to_variant(variant)
+                .replace('_', "-"),
+            RenameRule::ScreamingKebab => RenameRule::ScreamingSnake
+                .apply_to_variant(variant)
+                .replace('_', "-"),
+        }
+    }
+}
@@ -0,0 +1,298 @@
+use std::collections::BTreeMap;
+use std::fmt::Display;
+
+use nyoom_json::{Serializer, WriteToJson, WriteToJsonRef};
+use serde::Serialize;
+
+fn nyoom<T: WriteToJsonRef<String>>(value: &T) -> String {
+    let mut out = String::new();
+    Serializer::new(&mut out).write(value);
+    out
+}
+
+/// Checks the derived output against serde_json's, for types deriving both with the same attributes.
+fn assert_same<T: WriteToJsonRef<String> + Serialize>(value: &T) {
+    assert_eq!(nyoom(value), serde_json::to_string(value).unwrap());
+}
+
+macro_rules! rename_all {
+    ($($rule:literal => $fields:ident, $variants:ident;)*) => {
+        $(
+            #[derive(Serialize, WriteToJson)]
+            #[serde(rename_all = $rule)]
+            #[nyoom(rename_all = $rule)]
+            struct $fields {
+                cat_name: u8,
+                lives: u8,
+                r#type: u8,
+                meow_decibels_at_night: u8,
+            }
+
+            #[derive(Serialize, WriteToJson)]
+            #[serde(rename_all = $rule)]
+            #[nyoom(rename_all = $rule)]
+            enum $variants {
+                HouseCat,
+                Lion,
+                SabreToothTiger,
+            }
+
+            assert_same(&$fields {
+                cat_name: 1,
+                lives: 2,
+                r#type: 3,
+                meow_decibels_at_night: 4,
+            });
+            assert_same(&[$variants::HouseCat, $variants::Lion, $variants::SabreToothTiger]);
+        )*
+    };
+}
+
+#[test]
+fn rename_all_matches_serde() {
+    rename_all! {
+        "lowercase" => LowerFields, LowerVariants;
+        "UPPERCASE" => UpperFields, UpperVariants;
+        "PascalCase" => PascalFields, PascalVariants;
+        "camelCase" => CamelFields, CamelVariants;
+        "snake_case" => SnakeFields, SnakeVariants;
+        "SCREAMING_SNAKE_CASE" => ScreamingSnakeFields, ScreamingSnakeVariants;
+        "kebab-case" => KebabFields, KebabVariants;
+        "SCREAMING-KEBAB-CASE" => ScreamingKebabFields, ScreamingKebabVariants;
+    }
+}
+
+#[test]
+fn rename() {
+    #[derive(Serialize, WriteToJson)]
+    #[serde(rename_all = "camelCase")]
+    #[nyoom(rename_all = "camelCase")]
+    struct Cat {
+        #[serde(rename = "the name")]
+        #[nyoom(rename = "the name")]
+        cat_name: &'static str,
+        meow_decibels: u8,
+    }
+
+    assert_same(&Cat {
+        cat_name: "mittens",
+        meow_decibels: 45,
+    });
+}
+
+fn is_zero(v: &u8) -> bool {
+    *v == 0
+}
+
+#[derive(Serialize, WriteToJson)]
+struct Skips {
+    #[serde(skip)]
+    #[nyoom(skip)]
+    _secret: u8,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[nyoom(skip_if = "Option::is_none")]
+    illness: Option<&'static str>,
+    #[serde(skip_serializing_if = "is_zero")]
+    #[nyoom(skip_if = "is_zero")]
+    lives: u8,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[nyoom(skip_if = "Vec::is_empty")]
+    toys: Vec<&'static str>,
+}
+
+#[test]
+fn skip_and_skip_if() {
+    assert_same(&Skips {
+        _secret: 1,
+        illness: None,
+        lives: 0,
+        toys: vec![],
+    });
+    assert_same(&Skips {
+        _secret: 1,
+        illness: Some("sniffles"),
+        lives: 9,
+        toys: vec!["mouse"],
+    });
+}
+
+#[derive(Serialize, WriteToJson)]
+struct Owner {
+    owner_name: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[nyoom(skip_if = "Option::is_none")]
+    phone: Option<u32>,
+}
+
+#[derive(Serialize, WriteToJson)]
+struct Flattened {
+    name: &'static str,
+    #[serde(flatten)]
+    #[nyoom(flatten)]
+    owner: Owner,
+    #[serde(flatten)]
+    #[nyoom(flatten)]
+    extra: BTreeMap<&'static str, u8>,
+    #[serde(flatten)]
+    #[nyoom(flatten)]
+    maybe: Option<Owner>,
+    last: bool,
+}
+
+#[test]
+fn flatten() {
+    assert_same(&Flattened {
+        name: "mittens",
+        owner: Owner {
+            owner_name: "emily",
+            phone: None,
+        },
+        extra: BTreeMap::from([("lives", 9), ("age", 3)]),
+        maybe: None,
+        last: true,
+    });
+    assert_same(&Flattened {
+        name: "mittens",
+        owner: Owner {
+            owner_name: "emily",
+            phone: Some(555),
+        },
+        extra: BTreeMap::new(),
+        maybe: Some(Owner {
+            owner_name: "beans",
+            phone: None,
+        }),
+        last: false,
+    });
+}
+
+#[derive(Serialize, WriteToJson)]
+struct Generic<'a, T: Display, U = u8>
+where
+    U: Copy,
+{
+    label: &'a str,
+    value: T,
+    others: Vec<U>,
+}
+
+#[derive(Serialize, WriteToJson)]
+struct GenericNewtype<T>(T);
+
+#[derive(Serialize, WriteToJson)]
+enum GenericEnum<T, U> {
+    First(T),
+    Both(T, U),
+    Named { inner: Option<U> },
+}
+
+#[test]
+fn generics() {
+    assert_same(&Generic {
+        label: "cat",
+        value: "mittens",
+        others: vec![1u8, 2],
+    });
+    assert_same(&Generic::<_, &str> {
+        label: "nested",
+        value: 1.5,
+        others: vec!["a"],
+    });
+    assert_same(&GenericNewtype(Generic {
+        label: "deep",
+        value: 'x',
+        others: Vec::<u8>::new(),
+    }));
+    assert_same(&[
+        GenericEnum::First(1),
+        GenericEnum::Both(2, "two"),
+        GenericEnum::Named { inner: None },
+        GenericEnum::Named { inner: Some("x") },
+    ]);
+}
+
+#[derive(Serialize, WriteToJson)]
+struct Unit;
+
+#[derive(Serialize, WriteToJson)]
+struct Newtype(u8);
+
+#[derive(Serialize, WriteToJson)]
+struct Tuple(u8, &'static str, bool);
+
+#[derive(Serialize, WriteToJson)]
+struct Empty {}
+
+#[derive(Serialize, WriteToJson)]
+#[serde(rename_all = "snake_case")]
+#[nyoom(rename_all = "snake_case")]
+enum Toy {
+    Mouse,
+    #[serde(rename = "the ball")]
+    #[nyoom(rename = "the ball")]
+    Ball(f32),
+    Newtype(Newtype),
+    String(u32, bool),
+    #[serde(rename_all = "camelCase")]
+    #[nyoom(rename_all = "camelCase")]
+    CardboardBox {
+        box_width: u32,
+        box_height: u32,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        #[nyoom(skip_if = "Option::is_none")]
+        label: Option<&'static str>,
+    },
+    EmptyTuple(),
+    EmptyStruct {},
+    Nested(Vec<Toy>),
+}
+
+#[test]
+fn shapes_and_data_carrying_enums() {
+    assert_same(&Unit);
+    assert_same(&Newtype(7));
+    assert_same(&Tuple(1, "two", true));
+    assert_same(&Empty {});
+    assert_same(&[
+        Toy::Mouse,
+        Toy::Ball(2.5),
+        Toy::Newtype(Newtype(1)),
+        Toy::String(30, true),
+        Toy::CardboardBox {
+            box_width: 40,
+            box_height: 20,
+            label: None,
+        },
+        Toy::CardboardBox {
+            box_width: 1,
+            box_height: 2,
+            label: Some("fragile \"cat\""),
+        },
+        Toy::EmptyTuple(),
+        Toy::EmptyStruct {},
+        Toy::Nested(vec![Toy::Mouse, Toy::Nested(vec![])]),
+    ]);
+}
+
+#[test]
+fn by_value_and_by_reference() {
+    let toys = vec![Toy::Mouse, Toy::Ball(0.5)];
+
+    let mut out = String::new();
+    let mut ser = Serializer::new(&mut out);
+    let mut arr = ser.array();
+    arr.add(&toys[0]);
+    arr.add(Toy::String(1, false));
+    arr.extend(&toys);
+    arr.end();
+
+    assert_eq!(
+        out,
+        r#"["mouse",{"string":[1,false]},"mouse",{"the ball":0.5}]"#
+    );
+}
+
+#[test]
+fn compile_errors() {
+    trybuild::TestCases::new().compile_fail("tests/ui/*.rs");
+}
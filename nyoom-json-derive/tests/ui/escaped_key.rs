@@ -0,0 +1,9 @@
+use nyoom_json::WriteToJson;
+
+#[derive(WriteToJson)]
+struct Cat {
+    #[nyoom(rename = "needs\"escaping")]
+    name: String,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use nyoom_json::WriteToJson;
+
+#[derive(WriteToJson)]
+enum Toy {
+    #[nyoom(rename = "back\\slash")]
+    Mouse,
+}
+
+fn main() {}
@@ -0,0 +1,14 @@
+use nyoom_json::WriteToJson;
+
+#[derive(WriteToJson)]
+struct Owner {
+    name: String,
+}
+
+#[derive(WriteToJson)]
+struct Cat {
+    #[nyoom(flatten, rename = "owner")]
+    owner: Owner,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use nyoom_json::WriteToJson;
+
+#[derive(WriteToJson)]
+enum Toy {
+    #[nyoom(skip)]
+    Mouse,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use nyoom_json::WriteToJson;
+
+#[derive(WriteToJson)]
+union Bits {
+    int: u32,
+    float: f32,
+}
+
+fn main() {}
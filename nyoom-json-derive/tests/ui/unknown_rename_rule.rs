@@ -0,0 +1,9 @@
+use nyoom_json::WriteToJson;
+
+#[derive(WriteToJson)]
+#[nyoom(rename_all = "sPoNgEcAsE")]
+struct Cat {
+    name: String,
+}
+
+fn main() {}
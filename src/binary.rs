@@ -0,0 +1,212 @@
+use crate::{JsonBuffer, WriteToJson, WriteToJsonRef};
+
+/// How many encoded characters are buffered on the stack before being pushed into the output.
+const ENCODE_BUF: usize = 1024;
+
+const STANDARD: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+const URL_SAFE: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// Binary data that is written as a base64 encoded JSON string, encoding straight into the output.
+/// # Examples
+/// ```
+/// use nyoom_json::{Base64, Serializer};
+///
+/// let mut out = String::new();
+/// let mut ser = Serializer::new(&mut out);
+///
+/// let mut obj = ser.object();
+/// obj.field("standard", Base64::standard(b"kitten?>"));
+/// obj.field("url_safe", Base64::url_safe_no_pad(b"kitten?>"));
+/// obj.end();
+///
+/// assert_eq!(out, r#"{"standard":"a2l0dGVuPz4=","url_safe":"a2l0dGVuPz4"}"#);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Base64<'a> {
+    bytes: &'a [u8],
+    alphabet: &'static [u8; 64],
+    pad: bool,
+}
+
+impl<'a> Base64<'a> {
+    /// Standard base64 (RFC 4648 §4), with padding.
+    #[inline(always)]
+    pub fn standard(bytes: &'a [u8]) -> Base64<'a> {
+        Base64 {
+            bytes,
+            alphabet: STANDARD,
+            pad: true,
+        }
+    }
+
+    /// Standard base64 (RFC 4648 §4), without padding.
+    #[inline(always)]
+    pub fn standard_no_pad(bytes: &'a [u8]) -> Base64<'a> {
+        Base64 {
+            bytes,
+            alphabet: STANDARD,
+            pad: false,
+        }
+    }
+
+    /// URL and filename safe base64 (RFC 4648 §5), with padding.
+    #[inline(always)]
+    pub fn url_safe(bytes: &'a [u8]) -> Base64<'a> {
+        Base64 {
+            bytes,
+            alphabet: URL_SAFE,
+            pad: true,
+        }
+    }
+
+    /// URL and filename safe base64 (RFC 4648 §5), without padding.
+    #[inline(always)]
+    pub fn url_safe_no_pad(bytes: &'a [u8]) -> Base64<'a> {
+        Base64 {
+            bytes,
+            alphabet: URL_SAFE,
+            pad: false,
+        }
+    }
+
+    fn encoded_len(&self) -> usize {
+        match self.pad {
+            true => self.bytes.len().div_ceil(3) * 4,
+            false => (self.bytes.len() * 4).div_ceil(3),
+        }
+    }
+}
+
+impl<'a, S: JsonBuffer> WriteToJsonRef<S> for Base64<'a> {
+    fn write_to_json_ref(&self, out: &mut S) {
+        out.reserve(self.encoded_len() + 2);
+        out.push('"');
+
+        // chunks are a multiple of 3 bytes long, so only the last one can need padding
+        let mut buf = [0u8; ENCODE_BUF];
+        for chunk in self.bytes.chunks(ENCODE_BUF / 4 * 3) {
+            let len = encode_base64(chunk, self.alphabet, self.pad, &mut buf);
+            out.push_str(ascii(&buf[..len]));
+        }
+
+        out.push('"');
+    }
+}
+
+impl<'a, S: JsonBuffer> WriteToJson<S> for Base64<'a> {
+    #[inline(always)]
+    fn write_to_json(self, out: &mut S) {
+        self.write_to_json_ref(out)
+    }
+}
+
+fn encode_base64(input: &[u8], alphabet: &[u8; 64], pad: bool, buf: &mut [u8]) -> usize {
+    let mut len = 0;
+    let mut groups = input.chunks_exact(3);
+    for group in groups.by_ref() {
+        let n = u32::from_be_bytes([0, group[0], group[1], group[2]]);
+        buf[len] = alphabet[(n >> 18) as usize & 63];
+        buf[len + 1] = alphabet[(n >> 12) as usize & 63];
+        buf[len + 2] = alphabet[(n >> 6) as usize & 63];
+        buf[len + 3] = alphabet[n as usize & 63];
+        len += 4;
+    }
+
+    match *groups.remainder() {
+        [a] => {
+            buf[len] = alphabet[(a >> 2) as usize];
+            buf[len + 1] = alphabet[((a & 0b11) << 4) as usize];
+            len += 2;
+            if pad {
+                buf[len..len + 2].copy_from_slice(b"==");
+                len += 2;
+            }
+        }
+        [a, b] => {
+            buf[len] = alphabet[(a >> 2) as usize];
+            buf[len + 1] = alphabet[(((a & 0b11) << 4) | (b >> 4)) as usize];
+            buf[len + 2] = alphabet[((b & 0b1111) << 2) as usize];
+            len += 3;
+            if pad {
+                buf[len] = b'=';
+                len += 1;
+            }
+        }
+        _ => {}
+    }
+
+    len
+}
+
+/// Binary data that is written as a hex encoded JSON string, encoding straight into the output.
+/// # Examples
+/// ```
+/// use nyoom_json::{Hex, Serializer};
+///
+/// let mut out = String::new();
+/// let mut ser = Serializer::new(&mut out);
+///
+/// let mut arr = ser.array();
+/// arr.add(Hex::lower(&[0xca, 0xfe, 0x0b]));
+/// arr.add(Hex::upper(&[0xca, 0xfe, 0x0b]));
+/// arr.end();
+///
+/// assert_eq!(out, r#"["cafe0b","CAFE0B"]"#);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Hex<'a> {
+    bytes: &'a [u8],
+    digits: &'static [u8; 16],
+}
+
+impl<'a> Hex<'a> {
+    /// Hex with lowercase digits.
+    #[inline(always)]
+    pub fn lower(bytes: &'a [u8]) -> Hex<'a> {
+        Hex {
+            bytes,
+            digits: b"0123456789abcdef",
+        }
+    }
+
+    /// Hex with uppercase digits.
+    #[inline(always)]
+    pub fn upper(bytes: &'a [u8]) -> Hex<'a> {
+        Hex {
+            bytes,
+            digits: b"0123456789ABCDEF",
+        }
+    }
+}
+
+impl<'a, S: JsonBuffer> WriteToJsonRef<S> for Hex<'a> {
+    fn write_to_json_ref(&self, out: &mut S) {
+        out.reserve(self.bytes.len() * 2 + 2);
+        out.push('"');
+
+        let mut buf = [0u8; ENCODE_BUF];
+        for chunk in self.bytes.chunks(ENCODE_BUF / 2) {
+            for (i, byte) in chunk.iter().enumerate() {
+                buf[i * 2] = self.digits[(byte >> 4) as usize];
+                buf[i * 2 + 1] = self.digits[(byte & 0xF) as usize];
+            }
+            out.push_str(ascii(&buf[..chunk.len() * 2]));
+        }
+
+        out.push('"');
+    }
+}
+
+impl<'a, S: JsonBuffer> WriteToJson<S> for Hex<'a> {
+    #[inline(always)]
+    fn write_to_json(self, out: &mut S) {
+        self.write_to_json_ref(out)
+    }
+}
+
+#[inline(always)]
+fn ascii(encoded: &[u8]) -> &str {
+    debug_assert!(encoded.is_ascii());
+    // safety: everything we encode comes out of an ASCII alphabet
+    unsafe { core::str::from_utf8_unchecked(encoded) }
+}
@@ -0,0 +1,182 @@
+#[cfg(feature = "alloc")]
+use alloc::{
+    collections::{BTreeMap, BTreeSet, VecDeque},
+    vec::Vec,
+};
+#[cfg(feature = "std")]
+use std::collections::{HashMap, HashSet};
+
+use crate::{ArrayWriter, JsonBuffer, WriteToJson, WriteToJsonRef};
+#[cfg(feature = "alloc")]
+use crate::{Key, ObjectWriter};
+
+impl<S: JsonBuffer, T: WriteToJsonRef<S>> WriteToJsonRef<S> for [T] {
+    #[inline]
+    fn write_to_json_ref(&self, out: &mut S) {
+        ArrayWriter::start(out).extend(self)
+    }
+}
+
+impl<S: JsonBuffer, T: WriteToJsonRef<S>, const N: usize> WriteToJsonRef<S> for [T; N] {
+    #[inline]
+    fn write_to_json_ref(&self, out: &mut S) {
+        self.as_slice().write_to_json_ref(out)
+    }
+}
+
+impl<S: JsonBuffer, T: WriteToJson<S>, const N: usize> WriteToJson<S> for [T; N] {
+    #[inline]
+    fn write_to_json(self, out: &mut S) {
+        ArrayWriter::start(out).extend(self)
+    }
+}
+
+macro_rules! impl_seq {
+    ($feature:literal => $($ty:ident<T $(, $bound:ident)?>),*) => {
+        $(
+            #[cfg(feature = $feature)]
+            impl<S: JsonBuffer, T: WriteToJsonRef<S> $(, $bound)?> WriteToJsonRef<S> for $ty<T $(, $bound)?> {
+                #[inline]
+                fn write_to_json_ref(&self, out: &mut S) {
+                    ArrayWriter::start(out).extend(self)
+                }
+            }
+
+            #[cfg(feature = $feature)]
+            impl<S: JsonBuffer, T: WriteToJson<S> $(, $bound)?> WriteToJson<S> for $ty<T $(, $bound)?> {
+                #[inline]
+                fn write_to_json(self, out: &mut S) {
+                    ArrayWriter::start(out).extend(self)
+                }
+            }
+        )*
+    }
+}
+
+impl_seq!("alloc" => Vec<T>, VecDeque<T>, BTreeSet<T>);
+impl_seq!("std" => HashSet<T, H>);
+
+macro_rules! impl_map {
+    ($feature:literal => $($ty:ident<K, V $(, $bound:ident)?>),*) => {
+        $(
+            #[cfg(feature = $feature)]
+            impl<S: JsonBuffer, K: Key, V: WriteToJsonRef<S> $(, $bound)?> WriteToJsonRef<S> for $ty<K, V $(, $bound)?> {
+                #[inline]
+                fn write_to_json_ref(&self, out: &mut S) {
+                    let mut obj = ObjectWriter::start(out);
+                    for (k, v) in self {
+                        obj.field(k, v);
+                    }
+                }
+            }
+
+            #[cfg(feature = $feature)]
+            impl<S: JsonBuffer, K: Key, V: WriteToJson<S> $(, $bound)?> WriteToJson<S> for $ty<K, V $(, $bound)?> {
+                #[inline]
+                fn write_to_json(self, out: &mut S) {
+                    let mut obj = ObjectWriter::start(out);
+                    for (k, v) in self {
+                        obj.field(k, v);
+                    }
+                }
+            }
+        )*
+    }
+}
+
+impl_map!("alloc" => BTreeMap<K, V>);
+impl_map!("std" => HashMap<K, V, H>);
+
+/// A map that is written with its keys in sorted order, for deterministic output from maps that don't keep their keys in order, like `HashMap`.
+/// # Examples
+/// ```
+/// use nyoom_json::{Serializer, SortedKeys};
+/// use std::collections::HashMap;
+///
+/// let mut naps = HashMap::new();
+/// naps.insert("sofa", 3);
+/// naps.insert("bed", 5);
+/// naps.insert("windowsill", 2);
+///
+/// let mut out = String::new();
+/// let mut ser = Serializer::new(&mut out);
+/// ser.write(SortedKeys(&naps));
+///
+/// assert_eq!(out, r#"{"bed":5,"sofa":3,"windowsill":2}"#);
+/// ```
+#[cfg(feature = "std")]
+#[derive(Clone, Copy, Debug)]
+#[repr(transparent)]
+pub struct SortedKeys<M>(pub M);
+
+#[cfg(feature = "std")]
+impl<S, K, V, H> WriteToJson<S> for SortedKeys<&HashMap<K, V, H>>
+where
+    S: JsonBuffer,
+    K: Key + Ord,
+    V: WriteToJsonRef<S>,
+{
+    fn write_to_json(self, out: &mut S) {
+        let mut fields: Vec<(&K, &V)> = self.0.iter().collect();
+        fields.sort_unstable_by_key(|(k, _)| *k);
+
+        let mut obj = ObjectWriter::start(out);
+        for (k, v) in fields {
+            obj.field(k, v);
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<S, K, V, H> WriteToJsonRef<S> for SortedKeys<&HashMap<K, V, H>>
+where
+    S: JsonBuffer,
+    K: Key + Ord,
+    V: WriteToJsonRef<S>,
+{
+    #[inline(always)]
+    fn write_to_json_ref(&self, out: &mut S) {
+        SortedKeys(self.0).write_to_json(out)
+    }
+}
+
+macro_rules! impl_tuple {
+    ($(($($name:ident),+)),*) => {
+        $(
+            impl<S: JsonBuffer, $($name: WriteToJson<S>),+> WriteToJson<S> for ($($name,)+) {
+                #[inline]
+                #[allow(non_snake_case)]
+                fn write_to_json(self, out: &mut S) {
+                    let ($($name,)+) = self;
+                    let mut arr = ArrayWriter::start(out);
+                    $(arr.add($name);)+
+                }
+            }
+
+            impl<S: JsonBuffer, $($name: WriteToJsonRef<S>),+> WriteToJsonRef<S> for ($($name,)+) {
+                #[inline]
+                #[allow(non_snake_case)]
+                fn write_to_json_ref(&self, out: &mut S) {
+                    let ($($name,)+) = self;
+                    let mut arr = ArrayWriter::start(out);
+                    $(arr.add($name);)+
+                }
+            }
+        )*
+    }
+}
+
+impl_tuple!(
+    (A),
+    (A, B),
+    (A, B, C),
+    (A, B, C, D),
+    (A, B, C, D, E),
+    (A, B, C, D, E, F),
+    (A, B, C, D, E, F, G),
+    (A, B, C, D, E, F, G, H),
+    (A, B, C, D, E, F, G, H, I),
+    (A, B, C, D, E, F, G, H, I, J),
+    (A, B, C, D, E, F, G, H, I, J, K),
+    (A, B, C, D, E, F, G, H, I, J, K, L)
+);
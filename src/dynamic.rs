@@ -0,0 +1,74 @@
+use crate::{JsonBuffer, WriteToJsonRef};
+
+/// A dyn-compatible version of [WriteToJsonRef](WriteToJsonRef), which writes into a type-erased `&mut dyn JsonBuffer`.
+///
+/// Everything that is [WriteToJsonRef](WriteToJsonRef) for any buffer implements this, and `dyn DynWriteToJson` is [WriteToJsonRef](WriteToJsonRef) in turn, so values of different types can be kept together behind references or boxes and still be passed to `field`, `add` and `extend`.
+/// # Examples
+/// ```
+/// use nyoom_json::{DynWriteToJson, JsonBuffer, Serializer, WriteToJsonRef};
+///
+/// struct Cat {
+///     name: &'static str,
+/// }
+///
+/// impl<S: JsonBuffer> WriteToJsonRef<S> for Cat {
+///     fn write_to_json_ref(&self, out: &mut S) {
+///         let mut ser = Serializer::new(out);
+///         let mut obj = ser.object();
+///         obj.field("cat", self.name);
+///     }
+/// }
+///
+/// let records: Vec<Box<dyn DynWriteToJson>> = vec![
+///     Box::new(Cat { name: "mittens" }),
+///     Box::new(9),
+///     Box::new("beans"),
+///     Box::new(vec![true, false]),
+/// ];
+///
+/// let mut out = String::new();
+/// let mut ser = Serializer::new(&mut out);
+///
+/// let mut obj = ser.object();
+/// obj.field("first", &*records[0]);
+/// obj.complex_field("all", |mut ser| ser.array().extend(&records));
+/// obj.end();
+///
+/// assert_eq!(
+///     out,
+///     r#"{"first":{"cat":"mittens"},"all":[{"cat":"mittens"},9,"beans",[true,false]]}"#
+/// );
+/// ```
+pub trait DynWriteToJson {
+    fn write_dyn(&self, out: &mut dyn JsonBuffer);
+}
+
+impl<T> DynWriteToJson for T
+where
+    T: for<'a, 'b> WriteToJsonRef<&'a mut (dyn JsonBuffer + 'b)>,
+{
+    #[inline]
+    fn write_dyn(&self, mut out: &mut dyn JsonBuffer) {
+        self.write_to_json_ref(&mut out)
+    }
+}
+
+macro_rules! impl_dyn {
+    ($($ty:ty),*) => {
+        $(
+            impl<S: JsonBuffer> WriteToJsonRef<S> for $ty {
+                #[inline]
+                fn write_to_json_ref(&self, out: &mut S) {
+                    self.write_dyn(out)
+                }
+            }
+        )*
+    }
+}
+
+impl_dyn!(
+    dyn DynWriteToJson + '_,
+    dyn DynWriteToJson + Send + '_,
+    dyn DynWriteToJson + Sync + '_,
+    dyn DynWriteToJson + Send + Sync + '_
+);
@@ -2,6 +2,8 @@
 code sourced from https://github.com/dtolnay/miniserde, licensed under the MIT license
 */
 
+use core::fmt;
+
 use crate::{cold, JsonBuffer};
 
 // Clippy false positive: https://github.com/rust-lang/rust-clippy/issues/5169
@@ -48,6 +50,34 @@ pub(crate) fn escape_str<S: JsonBuffer>(value: &str, out: &mut S) {
     }
 }
 
+/// Escapes everything written into it, for streaming formatter output into a JSON string.
+pub(crate) struct Escaper<'a, S: JsonBuffer>(pub(crate) &'a mut S);
+
+impl<S: JsonBuffer> fmt::Write for Escaper<'_, S> {
+    #[inline(always)]
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        escape_str(s, self.0);
+        Ok(())
+    }
+}
+
+/// Writes everything written into it as-is, for formatter output that is known not to need escaping.
+pub(crate) struct Verbatim<'a, S: JsonBuffer>(pub(crate) &'a mut S);
+
+impl<S: JsonBuffer> fmt::Write for Verbatim<'_, S> {
+    #[inline(always)]
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.0.push_str(s);
+        Ok(())
+    }
+}
+
+/// Whether `byte` has to be escaped inside a JSON string, i.e whether it has a non-zero entry in [ESCAPE].
+#[inline(always)]
+pub(crate) const fn needs_escape(byte: u8) -> bool {
+    byte < 0x20 || byte == b'"' || byte == b'\\'
+}
+
 const BB: u8 = b'b'; // \x08
 const TT: u8 = b't'; // \x09
 const NN: u8 = b'n'; // \x0A
@@ -0,0 +1,139 @@
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+
+use crate::{validate, JsonBuffer, Key, KeySerializer, WriteToJson, WriteToJsonRef};
+
+/// A string that has already been escaped and quoted, and is written out with a single `push_str`.
+///
+/// Build one up front for strings that get written over and over again (enum names, tags, dictionary words), so they only pay for escaping once.
+/// # Examples
+/// ```
+/// use nyoom_json::{EscapedString, Serializer};
+///
+/// let greeting = EscapedString::new("say \"mew\"");
+/// assert_eq!(greeting.as_str(), r#""say \"mew\"""#);
+///
+/// let mut out = String::new();
+/// let mut ser = Serializer::new(&mut out);
+///
+/// let mut obj = ser.object();
+/// obj.field(&greeting, &greeting);
+/// obj.field("also", greeting.as_escaped());
+/// obj.end();
+///
+/// assert_eq!(out, r#"{"say \"mew\"":"say \"mew\"","also":"say \"mew\""}"#);
+/// ```
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct EscapedString(String);
+
+#[cfg(feature = "alloc")]
+impl EscapedString {
+    /// Escapes and quotes `val`.
+    pub fn new(val: &str) -> EscapedString {
+        let mut quoted = String::with_capacity(val.len() + 2);
+        val.write_to_json(&mut quoted);
+        EscapedString(quoted)
+    }
+
+    /// Borrows this as an [EscapedStr](EscapedStr).
+    #[inline(always)]
+    pub fn as_escaped(&self) -> EscapedStr<'_> {
+        EscapedStr(&self.0)
+    }
+
+    /// The escaped string, including its quotes.
+    #[inline(always)]
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Unwraps the escaped string, including its quotes.
+    pub fn into_string(self) -> String {
+        self.0
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<&str> for EscapedString {
+    fn from(val: &str) -> EscapedString {
+        EscapedString::new(val)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<S: JsonBuffer> WriteToJson<S> for EscapedString {
+    #[inline(always)]
+    fn write_to_json(self, out: &mut S) {
+        out.push_str(&self.0)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<S: JsonBuffer> WriteToJsonRef<S> for EscapedString {
+    #[inline(always)]
+    fn write_to_json_ref(&self, out: &mut S) {
+        out.push_str(&self.0)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Key for EscapedString {
+    #[inline(always)]
+    fn write_key<S: JsonBuffer>(&self, ser: KeySerializer<'_, S>) {
+        self.as_escaped().write_key(ser)
+    }
+}
+
+/// A borrowed string that has already been escaped and quoted. See [EscapedString](EscapedString).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[repr(transparent)]
+pub struct EscapedStr<'a>(&'a str);
+
+impl<'a> EscapedStr<'a> {
+    /// Wraps an already escaped and quoted string, without checking it. You should only use this if you're *absolutely* sure `val` is a valid JSON string literal, quotes included; it's only checked in debug builds.
+    /// # Examples
+    /// ```
+    /// use nyoom_json::EscapedStr;
+    ///
+    /// let tab = EscapedStr::create(r#""\t""#);
+    /// ```
+    #[inline(always)]
+    pub fn create(val: &'a str) -> EscapedStr<'a> {
+        debug_assert!(
+            validate::is_string(val.as_bytes()),
+            "string is not a quoted and escaped JSON string!"
+        );
+
+        EscapedStr(val)
+    }
+
+    /// The escaped string, including its quotes.
+    #[inline(always)]
+    pub fn as_str(&self) -> &'a str {
+        self.0
+    }
+}
+
+impl<'a, S: JsonBuffer> WriteToJson<S> for EscapedStr<'a> {
+    #[inline(always)]
+    fn write_to_json(self, out: &mut S) {
+        out.push_str(self.0)
+    }
+}
+
+impl<'a, S: JsonBuffer> WriteToJsonRef<S> for EscapedStr<'a> {
+    #[inline(always)]
+    fn write_to_json_ref(&self, out: &mut S) {
+        out.push_str(self.0)
+    }
+}
+
+impl Key for EscapedStr<'_> {
+    #[inline(always)]
+    fn write_key<S: JsonBuffer>(&self, ser: KeySerializer<'_, S>) {
+        let buf = ser.into_buf();
+        buf.push_str(self.0);
+        buf.push(':');
+    }
+}
@@ -0,0 +1,78 @@
+#[cfg(feature = "alloc")]
+use alloc::collections::BTreeMap;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+
+#[cfg(feature = "alloc")]
+use crate::Key;
+#[cfg(feature = "alloc")]
+use crate::WriteToJsonRef;
+use crate::{JsonBuffer, ObjectWriter};
+
+/// A value that writes its fields into an object someone else has opened, so that it can be flattened into another object.
+///
+/// `#[derive(WriteToJson)]` implements this for structs with named fields; maps implement it too, which makes them handy for catching extra fields.
+/// # Examples
+/// ```
+/// use nyoom_json::{JsonBuffer, ObjectWriter, Serializer, WriteFields};
+///
+/// struct Owner<'a> {
+///     name: &'a str,
+/// }
+///
+/// impl<S: JsonBuffer> WriteFields<S> for Owner<'_> {
+///     fn write_fields(&self, obj: &mut ObjectWriter<'_, S>) {
+///         obj.field("owner", self.name);
+///     }
+/// }
+///
+/// let mut out = String::new();
+/// let mut ser = Serializer::new(&mut out);
+///
+/// let mut obj = ser.object();
+/// obj.field("kind", "cat");
+/// obj.flatten(&Owner { name: "emily" });
+/// obj.end();
+///
+/// assert_eq!(out, r#"{"kind":"cat","owner":"emily"}"#);
+/// ```
+pub trait WriteFields<S: JsonBuffer> {
+    fn write_fields(&self, obj: &mut ObjectWriter<'_, S>);
+}
+
+impl<S: JsonBuffer, T: WriteFields<S> + ?Sized> WriteFields<S> for &T {
+    #[inline(always)]
+    fn write_fields(&self, obj: &mut ObjectWriter<'_, S>) {
+        (**self).write_fields(obj)
+    }
+}
+
+/// Writes nothing at all when `None`.
+impl<S: JsonBuffer, T: WriteFields<S>> WriteFields<S> for Option<T> {
+    #[inline(always)]
+    fn write_fields(&self, obj: &mut ObjectWriter<'_, S>) {
+        if let Some(v) = self {
+            v.write_fields(obj)
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<S: JsonBuffer, K: Key, V: WriteToJsonRef<S>> WriteFields<S> for BTreeMap<K, V> {
+    #[inline]
+    fn write_fields(&self, obj: &mut ObjectWriter<'_, S>) {
+        for (k, v) in self {
+            obj.field(k, v);
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<S: JsonBuffer, K: Key, V: WriteToJsonRef<S>, H> WriteFields<S> for HashMap<K, V, H> {
+    #[inline]
+    fn write_fields(&self, obj: &mut ObjectWriter<'_, S>) {
+        for (k, v) in self {
+            obj.field(k, v);
+        }
+    }
+}
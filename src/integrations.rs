@@ -0,0 +1,325 @@
+//! Implementations for types from other crates, each behind a cargo feature of the same name.
+
+#[cfg(any(feature = "rust_decimal", feature = "serde_json"))]
+use core::fmt::Write;
+
+#[cfg(any(feature = "rust_decimal", feature = "serde_json"))]
+use crate::escape::Verbatim;
+#[cfg(any(feature = "chrono", feature = "time"))]
+use crate::time::{write_rfc3339, write_timestamp, DateTime};
+#[cfg(any(feature = "chrono", feature = "time"))]
+use crate::TimestampFormat;
+use crate::{JsonBuffer, WriteToJson, WriteToJsonRef};
+#[cfg(feature = "uuid")]
+use crate::{Key, KeySerializer};
+
+/// Implements [WriteToJson](WriteToJson) for types that are written by reference.
+// (chrono's generic `DateTime<Tz>` is implemented by hand, so this goes unused when it's the only integration enabled)
+#[allow(unused_macros)]
+macro_rules! impl_by_ref {
+    ($($ty:ty),*) => {
+        $(
+            impl<S: JsonBuffer> WriteToJson<S> for $ty {
+                #[inline(always)]
+                fn write_to_json(self, out: &mut S) {
+                    self.write_to_json_ref(out)
+                }
+            }
+        )*
+    }
+}
+
+/// Written as a lowercase, hyphenated string, formatted on the stack.
+/// # Examples
+/// ```
+/// use nyoom_json::Serializer;
+/// use uuid::Uuid;
+///
+/// let id = Uuid::from_u128(0x67e55044_10b1_426f_9247_bb680e5fe0c8);
+///
+/// let mut out = String::new();
+/// let mut ser = Serializer::new(&mut out);
+///
+/// let mut obj = ser.object();
+/// obj.field("id", id);
+/// obj.field(id, "mittens");
+/// obj.end();
+///
+/// assert_eq!(
+///     out,
+///     r#"{"id":"67e55044-10b1-426f-9247-bb680e5fe0c8","67e55044-10b1-426f-9247-bb680e5fe0c8":"mittens"}"#
+/// );
+/// ```
+#[cfg(feature = "uuid")]
+impl<S: JsonBuffer> WriteToJsonRef<S> for uuid::Uuid {
+    #[inline]
+    fn write_to_json_ref(&self, out: &mut S) {
+        out.push('"');
+        out.push_str(
+            self.hyphenated()
+                .encode_lower(&mut uuid::Uuid::encode_buffer()),
+        );
+        out.push('"');
+    }
+}
+
+#[cfg(feature = "uuid")]
+impl_by_ref!(uuid::Uuid);
+
+#[cfg(feature = "uuid")]
+impl Key for uuid::Uuid {
+    #[inline]
+    fn write_key<S: JsonBuffer>(&self, ser: KeySerializer<'_, S>) {
+        ser.write_unescaped(
+            self.hyphenated()
+                .encode_lower(&mut uuid::Uuid::encode_buffer()),
+        )
+    }
+}
+
+/// Written according to the buffer's [TimestampFormat](crate::TimestampFormat). RFC 3339 timestamps keep the date-time's offset, unless it has a seconds part RFC 3339 can't represent, in which case they're written in UTC.
+/// # Examples
+/// ```
+/// use chrono::{FixedOffset, TimeZone};
+/// use nyoom_json::{Options, Serializer, TimestampFormat, WithOptions};
+///
+/// let dinner = FixedOffset::east_opt(2 * 3600)
+///     .unwrap()
+///     .with_ymd_and_hms(2023, 4, 1, 14, 30, 0)
+///     .unwrap();
+///
+/// let mut out = String::new();
+/// let mut ser = Serializer::new(&mut out);
+/// ser.write(dinner);
+/// assert_eq!(out, r#""2023-04-01T14:30:00+02:00""#);
+///
+/// let mut out = String::new();
+/// let mut buf = WithOptions::new(
+///     &mut out,
+///     Options::new().timestamp_format(TimestampFormat::UnixSeconds),
+/// );
+/// let mut ser = Serializer::new(&mut buf);
+/// ser.write(dinner);
+/// assert_eq!(out, "1680352200.0");
+/// ```
+#[cfg(feature = "chrono")]
+impl<S, Tz> WriteToJsonRef<S> for chrono::DateTime<Tz>
+where
+    S: JsonBuffer,
+    Tz: chrono::TimeZone,
+{
+    fn write_to_json_ref(&self, out: &mut S) {
+        use chrono::{Datelike, Offset, Timelike};
+
+        // leap seconds are represented as nanoseconds past the end of the previous second
+        let nanos = self.timestamp_subsec_nanos();
+        let (leap, nanos) = match nanos.checked_sub(1_000_000_000) {
+            Some(nanos) => (1, nanos),
+            None => (0, nanos),
+        };
+
+        match out.options().timestamp_format {
+            TimestampFormat::Rfc3339 => {
+                // RFC 3339 offsets are whole minutes, so anything finer is written in UTC instead
+                let (local, offset_seconds) = match self.offset().fix().local_minus_utc() {
+                    offset if offset % 60 == 0 => (self.naive_local(), offset),
+                    _ => (self.naive_utc(), 0),
+                };
+                write_rfc3339(
+                    out,
+                    DateTime {
+                        year: local.year() as i64,
+                        month: local.month(),
+                        day: local.day(),
+                        hour: local.hour(),
+                        minute: local.minute(),
+                        second: local.second() + leap,
+                        nanos,
+                        offset_seconds,
+                    },
+                )
+            }
+            format => write_timestamp(out, self.timestamp(), nanos, format),
+        }
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl<S, Tz> WriteToJson<S> for chrono::DateTime<Tz>
+where
+    S: JsonBuffer,
+    Tz: chrono::TimeZone,
+{
+    #[inline(always)]
+    fn write_to_json(self, out: &mut S) {
+        self.write_to_json_ref(out)
+    }
+}
+
+/// Written according to the buffer's [TimestampFormat](crate::TimestampFormat). RFC 3339 timestamps keep the date-time's offset, unless it has a seconds part RFC 3339 can't represent, in which case they're written in UTC.
+/// # Examples
+/// ```
+/// use nyoom_json::Serializer;
+/// use time::{OffsetDateTime, UtcOffset};
+///
+/// let dinner = OffsetDateTime::from_unix_timestamp_nanos(1_680_370_200_250_000_000)
+///     .unwrap()
+///     .to_offset(UtcOffset::from_hms(-5, 0, 0).unwrap());
+///
+/// let mut out = String::new();
+/// let mut ser = Serializer::new(&mut out);
+/// ser.write(dinner);
+///
+/// assert_eq!(out, r#""2023-04-01T12:30:00.250-05:00""#);
+/// ```
+#[cfg(feature = "time")]
+impl<S: JsonBuffer> WriteToJsonRef<S> for time::OffsetDateTime {
+    fn write_to_json_ref(&self, out: &mut S) {
+        match out.options().timestamp_format {
+            // RFC 3339 offsets are whole minutes, so anything finer is written in UTC instead
+            TimestampFormat::Rfc3339 if self.offset().seconds_past_minute() != 0 => {
+                write_timestamp(
+                    out,
+                    self.unix_timestamp(),
+                    self.nanosecond(),
+                    TimestampFormat::Rfc3339,
+                )
+            }
+            TimestampFormat::Rfc3339 => write_rfc3339(
+                out,
+                DateTime {
+                    year: self.year() as i64,
+                    month: self.month() as u32,
+                    day: self.day() as u32,
+                    hour: self.hour() as u32,
+                    minute: self.minute() as u32,
+                    second: self.second() as u32,
+                    nanos: self.nanosecond(),
+                    offset_seconds: self.offset().whole_seconds(),
+                },
+            ),
+            format => write_timestamp(out, self.unix_timestamp(), self.nanosecond(), format),
+        }
+    }
+}
+
+#[cfg(feature = "time")]
+impl_by_ref!(time::OffsetDateTime);
+
+/// Written as a JSON number, with all of its digits.
+/// # Examples
+/// ```
+/// use nyoom_json::Serializer;
+/// use rust_decimal::Decimal;
+///
+/// let mut out = String::new();
+/// let mut ser = Serializer::new(&mut out);
+///
+/// let mut arr = ser.array();
+/// arr.add(Decimal::new(-1050, 2));
+/// arr.add(Decimal::MAX);
+/// arr.end();
+///
+/// assert_eq!(out, "[-10.50,79228162514264337593543950335]");
+/// ```
+#[cfg(feature = "rust_decimal")]
+impl<S: JsonBuffer> WriteToJsonRef<S> for rust_decimal::Decimal {
+    #[inline]
+    fn write_to_json_ref(&self, out: &mut S) {
+        // decimals always display as plain digits, without an exponent
+        let _ = write!(Verbatim(out), "{}", self);
+    }
+}
+
+#[cfg(feature = "rust_decimal")]
+impl_by_ref!(rust_decimal::Decimal);
+
+/// Written as a string.
+/// # Examples
+/// ```
+/// use nyoom_json::Serializer;
+/// use url::Url;
+///
+/// let url = Url::parse("https://example.com/cats?name=mittens").unwrap();
+///
+/// let mut out = String::new();
+/// let mut ser = Serializer::new(&mut out);
+/// ser.write(&url);
+///
+/// assert_eq!(out, r#""https://example.com/cats?name=mittens""#);
+/// ```
+#[cfg(feature = "url")]
+impl<S: JsonBuffer> WriteToJsonRef<S> for url::Url {
+    #[inline(always)]
+    fn write_to_json_ref(&self, out: &mut S) {
+        self.as_str().write_to_json_ref(out)
+    }
+}
+
+#[cfg(feature = "url")]
+impl_by_ref!(url::Url);
+
+/// Written as-is, walking the value directly rather than going through serde.
+/// # Examples
+/// ```
+/// use nyoom_json::Serializer;
+/// use serde_json::json;
+///
+/// let cat = json!({ "name": "mittens", "lives": 9, "toys": ["mouse", null] });
+///
+/// let mut out = String::new();
+/// let mut ser = Serializer::new(&mut out);
+/// ser.write(&cat);
+///
+/// assert_eq!(out, serde_json::to_string(&cat).unwrap());
+/// ```
+#[cfg(feature = "serde_json")]
+impl<S: JsonBuffer> WriteToJsonRef<S> for serde_json::Value {
+    fn write_to_json_ref(&self, out: &mut S) {
+        use serde_json::Value;
+
+        match self {
+            Value::Null => out.push_str("null"),
+            Value::Bool(b) => b.write_to_json(out),
+            Value::Number(n) => n.write_to_json_ref(out),
+            Value::String(s) => s.write_to_json_ref(out),
+            Value::Array(arr) => crate::ArrayWriter::start(out).extend(arr),
+            Value::Object(map) => map.write_to_json_ref(out),
+        }
+    }
+}
+
+#[cfg(feature = "serde_json")]
+impl<S: JsonBuffer> WriteToJsonRef<S> for serde_json::Number {
+    #[inline]
+    fn write_to_json_ref(&self, out: &mut S) {
+        if let Some(n) = self.as_u64() {
+            n.write_to_json(out)
+        } else if let Some(n) = self.as_i64() {
+            n.write_to_json(out)
+        } else {
+            // floats, or anything else `arbitrary_precision` lets through, already display as valid JSON
+            let _ = write!(Verbatim(out), "{}", self);
+        }
+    }
+}
+
+#[cfg(feature = "serde_json")]
+impl<S: JsonBuffer> WriteToJsonRef<S>
+    for serde_json::Map<alloc::string::String, serde_json::Value>
+{
+    #[inline]
+    fn write_to_json_ref(&self, out: &mut S) {
+        let mut obj = crate::ObjectWriter::start(out);
+        for (k, v) in self {
+            obj.field(k.as_str(), v);
+        }
+    }
+}
+
+#[cfg(feature = "serde_json")]
+impl_by_ref!(
+    serde_json::Value,
+    serde_json::Number,
+    serde_json::Map<alloc::string::String, serde_json::Value>
+);
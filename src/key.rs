@@ -0,0 +1,243 @@
+use core::fmt::{self, Write};
+use core::mem::ManuallyDrop;
+
+#[cfg(feature = "alloc")]
+use alloc::{borrow::Cow, boxed::Box, rc::Rc, string::String, sync::Arc};
+
+use crate::{
+    escape::{self, escape_str, Escaper},
+    DisplayStr, JsonBuffer, UnescapedStr,
+};
+
+/// A key for a JSON object's field.
+///
+/// Implement this for your own types to use them as keys directly. The [KeySerializer](KeySerializer) a key is written through takes care of quoting and escaping, so a key can't produce broken JSON.
+///
+/// Older versions implemented this for every `AsRef<str>` type. That blanket impl overlapped with the integer and `char` keys and has been removed.
+/// The standard string types (`str`, `String`, `Box<str>`, `Cow<str>`, `Rc<str>`, `Arc<str>`) are still keys. Other `AsRef<str>` keys can be passed as `key.as_ref()`, or given an impl that calls [write_str](KeySerializer::write_str).
+/// # Examples
+/// ```
+/// use nyoom_json::{JsonBuffer, Key, KeySerializer, Serializer};
+///
+/// struct CatId(u32);
+///
+/// impl Key for CatId {
+///     fn write_key<S: JsonBuffer>(&self, ser: KeySerializer<'_, S>) {
+///         ser.write_display(format_args!("cat-{}", self.0))
+///     }
+/// }
+///
+/// let mut out = String::new();
+/// let mut ser = Serializer::new(&mut out);
+///
+/// let mut obj = ser.object();
+/// obj.field(CatId(7), "mittens");
+/// obj.field(8, "tabby");
+/// obj.field('🐈', true);
+/// obj.end();
+///
+/// assert_eq!(out, r#"{"cat-7":"mittens","8":"tabby","🐈":true}"#);
+/// ```
+pub trait Key {
+    fn write_key<S: JsonBuffer>(&self, ser: KeySerializer<'_, S>);
+}
+
+/// A serializer that is only able to write a single object key (and the colon after it).
+///
+/// If the serializer is dropped without writing anything, an empty key is written.
+pub struct KeySerializer<'a, S: JsonBuffer> {
+    guard: ManuallyDrop<&'a mut S>,
+}
+
+impl<'a, S: JsonBuffer> KeySerializer<'a, S> {
+    pub(crate) fn new(buf: &'a mut S) -> KeySerializer<'a, S> {
+        KeySerializer {
+            guard: ManuallyDrop::new(buf),
+        }
+    }
+
+    /// Takes the buffer, leaving it up to the caller to write a complete key and colon.
+    #[inline(always)]
+    pub(crate) fn into_buf(mut self) -> &'a mut S {
+        let buf = unsafe { ManuallyDrop::<&'a mut S>::take(&mut self.guard) };
+        core::mem::forget(self);
+        buf
+    }
+
+    /// Writes a string as the key, escaping it as needed.
+    #[inline(always)]
+    pub fn write_str(self, key: &str) {
+        let buf = self.into_buf();
+        buf.push('"');
+        escape_str(key, buf);
+        buf.push_str("\":");
+    }
+
+    /// Writes the output of a [Display](core::fmt::Display) implementation as the key, escaping it as needed.
+    pub fn write_display(self, key: impl fmt::Display) {
+        let buf = self.into_buf();
+        buf.push('"');
+        let _ = write!(Escaper(buf), "{}", key);
+        buf.push_str("\":");
+    }
+
+    /// Writes a string that doesn't need escaping as the key.
+    #[inline(always)]
+    pub(crate) fn write_unescaped(self, key: &str) {
+        let buf = self.into_buf();
+        buf.push('"');
+        buf.push_str(key);
+        buf.push_str("\":");
+    }
+}
+
+impl<'a, S: JsonBuffer> Drop for KeySerializer<'a, S> {
+    fn drop(&mut self) {
+        unsafe { ManuallyDrop::<&'a mut S>::take(&mut self.guard).push_str("\"\":") };
+    }
+}
+
+impl<T: Key + ?Sized> Key for &T {
+    #[inline(always)]
+    fn write_key<S: JsonBuffer>(&self, ser: KeySerializer<'_, S>) {
+        (**self).write_key(ser)
+    }
+}
+
+impl Key for str {
+    #[inline(always)]
+    fn write_key<S: JsonBuffer>(&self, ser: KeySerializer<'_, S>) {
+        ser.write_str(self)
+    }
+}
+
+macro_rules! impl_str_key {
+    ($($ty:ty),*) => {
+        $(
+            #[cfg(feature = "alloc")]
+            impl Key for $ty {
+                #[inline(always)]
+                fn write_key<S: JsonBuffer>(&self, ser: KeySerializer<'_, S>) {
+                    ser.write_str(self)
+                }
+            }
+        )*
+    }
+}
+
+impl_str_key!(String, Box<str>, Cow<'_, str>, Rc<str>, Arc<str>);
+
+macro_rules! impl_int_key {
+    ($($ty:ty),*) => {
+        $(
+            impl Key for $ty {
+                #[inline(always)]
+                fn write_key<S: JsonBuffer>(&self, ser: KeySerializer<'_, S>) {
+                    let mut int_buf = itoa::Buffer::new();
+                    ser.write_unescaped(int_buf.format(*self))
+                }
+            }
+        )*
+    }
+}
+
+impl_int_key!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+impl Key for char {
+    #[inline(always)]
+    fn write_key<S: JsonBuffer>(&self, ser: KeySerializer<'_, S>) {
+        ser.write_str(self.encode_utf8(&mut [0; 4]))
+    }
+}
+
+impl Key for UnescapedStr<'_> {
+    #[inline(always)]
+    fn write_key<S: JsonBuffer>(&self, ser: KeySerializer<'_, S>) {
+        ser.write_unescaped(self.0)
+    }
+}
+
+impl<T: fmt::Display> Key for DisplayStr<T> {
+    #[inline(always)]
+    fn write_key<S: JsonBuffer>(&self, ser: KeySerializer<'_, S>) {
+        ser.write_display(&self.0)
+    }
+}
+
+/// An object key that has been checked and rendered at compile time, quotes and colon included, so writing it is a single `push_str`.
+///
+/// Create these with the [key!](crate::key) macro.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct StaticKey(&'static str);
+
+impl StaticKey {
+    /// Wraps a fully rendered key, like `"\"name\":"`. Prefer the [key!](crate::key) macro, which renders the key for you.
+    ///
+    /// # Panics
+    ///
+    /// Panics (at compile time, when used in a `const`) if `rendered` isn't a quoted key followed by a colon, or if the key contains characters that need to be escaped.
+    pub const fn from_rendered(rendered: &'static str) -> StaticKey {
+        let bytes = rendered.as_bytes();
+        let len = bytes.len();
+        assert!(
+            len >= 3 && bytes[0] == b'"' && bytes[len - 2] == b'"' && bytes[len - 1] == b':',
+            "key is not rendered as \"name\":"
+        );
+
+        let mut i = 1;
+        while i < len - 2 {
+            assert!(
+                !escape::needs_escape(bytes[i]),
+                "key contains characters that need to be escaped!"
+            );
+            i += 1;
+        }
+
+        StaticKey(rendered)
+    }
+
+    /// The rendered key, including its quotes and colon.
+    #[inline(always)]
+    pub const fn as_str(&self) -> &'static str {
+        self.0
+    }
+}
+
+impl Key for StaticKey {
+    #[inline(always)]
+    fn write_key<S: JsonBuffer>(&self, ser: KeySerializer<'_, S>) {
+        ser.into_buf().push_str(self.0)
+    }
+}
+
+/// Creates a [StaticKey](StaticKey) from a string literal, failing to compile if the key would need escaping.
+/// # Examples
+///
+/// ```
+/// use nyoom_json::{key, Serializer, StaticKey};
+///
+/// const KIND: StaticKey = key!("kind");
+///
+/// let mut out = String::new();
+/// let mut ser = Serializer::new(&mut out);
+///
+/// let mut obj = ser.object();
+/// obj.field(KIND, "cat");
+/// obj.field(key!("meow_decibels"), 45);
+/// obj.end();
+///
+/// assert_eq!(out, r#"{"kind":"cat","meow_decibels":45}"#);
+/// ```
+///
+/// ```compile_fail
+/// let bad = nyoom_json::key!("needs\nescaping");
+/// ```
+#[macro_export]
+macro_rules! key {
+    ($name:literal) => {{
+        const KEY: $crate::StaticKey =
+            $crate::StaticKey::from_rendered(concat!("\"", $name, "\":"));
+        KEY
+    }};
+}
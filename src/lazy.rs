@@ -0,0 +1,118 @@
+use core::marker::PhantomData;
+
+use crate::{ArrayWriter, JsonBuffer, Key, ObjectWriter, SingleValueSerializer, WriteToJson};
+
+/// An iterator that is written as a JSON array. The iterator isn't consumed until the array is written.
+/// # Examples
+/// ```
+/// use nyoom_json::{JsonBuffer, LazyArray, Serializer, WriteToJson};
+///
+/// fn lives_left<'a, S: JsonBuffer>(cats: &'a [(&str, u8)]) -> impl WriteToJson<S> + 'a {
+///     LazyArray(cats.iter().map(|(_, lives)| *lives))
+/// }
+///
+/// let cats = [("mittens", 9), ("beans", 8)];
+///
+/// let mut out = String::new();
+/// let mut ser = Serializer::new(&mut out);
+///
+/// let mut obj = ser.object();
+/// obj.field("lives", lives_left(&cats));
+/// obj.end();
+///
+/// assert_eq!(out, r#"{"lives":[9,8]}"#);
+/// ```
+#[derive(Clone, Debug)]
+pub struct LazyArray<I>(pub I);
+
+impl<S, I> WriteToJson<S> for LazyArray<I>
+where
+    S: JsonBuffer,
+    I: IntoIterator,
+    I::Item: WriteToJson<S>,
+{
+    #[inline]
+    fn write_to_json(self, out: &mut S) {
+        ArrayWriter::start(out).extend(self.0)
+    }
+}
+
+/// An iterator of key-value pairs that is written as a JSON object. The iterator isn't consumed until the object is written.
+/// # Examples
+/// ```
+/// use nyoom_json::{LazyObject, Serializer};
+///
+/// let cats = [("mittens", 9), ("beans", 8)];
+///
+/// let mut out = String::new();
+/// let mut ser = Serializer::new(&mut out);
+/// ser.write(LazyObject(cats.iter().filter(|(_, lives)| *lives > 8).copied()));
+///
+/// assert_eq!(out, r#"{"mittens":9}"#);
+/// ```
+#[derive(Clone, Debug)]
+pub struct LazyObject<I>(pub I);
+
+impl<S, I, K, V> WriteToJson<S> for LazyObject<I>
+where
+    S: JsonBuffer,
+    I: IntoIterator<Item = (K, V)>,
+    K: Key,
+    V: WriteToJson<S>,
+{
+    #[inline]
+    fn write_to_json(self, out: &mut S) {
+        let mut obj = ObjectWriter::start(out);
+        for (k, v) in self.0 {
+            obj.field(k, v);
+        }
+    }
+}
+
+/// A closure that writes a single value when called with a [SingleValueSerializer](SingleValueSerializer). The closure isn't called until the value is written.
+/// # Examples
+/// ```
+/// use nyoom_json::{LazyValue, Serializer};
+///
+/// let mut out = String::new();
+/// let mut ser = Serializer::new(&mut out);
+///
+/// let mut arr = ser.array();
+/// arr.add(LazyValue::new(|ser| {
+///     let mut obj = ser.object();
+///     obj.field("kitten", true);
+/// }));
+/// arr.end();
+///
+/// assert_eq!(out, r#"[{"kitten":true}]"#);
+/// ```
+pub struct LazyValue<S, F> {
+    encode: F,
+    _buf: PhantomData<fn(&mut S)>,
+}
+
+impl<S, F> LazyValue<S, F>
+where
+    S: JsonBuffer,
+    F: FnOnce(SingleValueSerializer<'_, S>),
+{
+    /// Wraps a closure that encodes a single value.
+    #[inline(always)]
+    pub fn new(encode: F) -> LazyValue<S, F> {
+        LazyValue {
+            encode,
+            _buf: PhantomData,
+        }
+    }
+}
+
+impl<S, F> WriteToJson<S> for LazyValue<S, F>
+where
+    S: JsonBuffer,
+    F: FnOnce(SingleValueSerializer<'_, S>),
+{
+    #[inline(always)]
+    fn write_to_json(self, out: &mut S) {
+        (self.encode)(SingleValueSerializer::new(out))
+    }
+}
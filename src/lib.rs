@@ -1,19 +1,81 @@
 #![doc = include_str!("../README.md")]
 #![no_std]
 
-use core::mem::ManuallyDrop;
+use core::{fmt, mem::ManuallyDrop};
+
+use escape::escape_str;
 
 #[cfg(feature = "alloc")]
 extern crate alloc;
 #[cfg(feature = "alloc")]
 use alloc::string::String;
 
-use sealed::sealed;
+#[cfg(feature = "std")]
+extern crate std;
 
+mod binary;
+mod collections;
+mod dynamic;
 mod escape;
+mod escaped;
+mod fields;
+#[cfg(any(
+    feature = "uuid",
+    feature = "chrono",
+    feature = "time",
+    feature = "rust_decimal",
+    feature = "url",
+    feature = "serde_json"
+))]
+mod integrations;
+mod key;
+mod lazy;
+mod macros;
+mod options;
+mod raw;
+#[cfg(feature = "std")]
+mod read;
+#[cfg(feature = "serde")]
+mod serde_bridge;
+#[cfg(any(feature = "valuable", feature = "sval"))]
+mod structured;
+mod tagged;
+mod template;
+mod time;
+mod validate;
+#[cfg(feature = "alloc")]
+mod value;
 mod write_to_json;
+pub use binary::*;
+#[cfg(feature = "std")]
+pub use collections::*;
+pub use dynamic::*;
+pub use escaped::*;
+pub use fields::*;
+pub use key::*;
+pub use lazy::*;
+pub use options::*;
+pub use raw::*;
+#[cfg(feature = "std")]
+pub use read::*;
+#[cfg(feature = "serde")]
+pub use serde_bridge::*;
+#[cfg(any(feature = "valuable", feature = "sval"))]
+pub use structured::*;
+pub use time::*;
+#[cfg(feature = "alloc")]
+pub use value::*;
 pub use write_to_json::*;
 
+#[cfg(feature = "derive")]
+pub use nyoom_json_derive::WriteToJson;
+
+#[doc(hidden)]
+pub mod __private {
+    pub use crate::template::segments;
+    pub use crate::validate::{minified_len, minified_str, minify, Holes, Minified};
+}
+
 #[inline]
 #[cold]
 fn cold() {}
@@ -23,11 +85,17 @@ pub trait JsonBuffer {
     fn push(&mut self, c: char);
     fn push_str(&mut self, s: &str);
     fn reserve(&mut self, l: usize);
+
+    /// The [Options](Options) in effect for values written into this buffer.
+    #[inline(always)]
+    fn options(&self) -> Options {
+        Options::DEFAULT
+    }
 }
 
 impl<S> JsonBuffer for &mut S
 where
-    S: JsonBuffer,
+    S: JsonBuffer + ?Sized,
 {
     #[inline(always)]
     fn push(&mut self, c: char) {
@@ -43,6 +111,11 @@ where
     fn reserve(&mut self, l: usize) {
         (*self).reserve(l)
     }
+
+    #[inline(always)]
+    fn options(&self) -> Options {
+        (**self).options()
+    }
 }
 
 #[cfg(feature = "alloc")]
@@ -92,7 +165,7 @@ pub struct Serializer<'a, S: JsonBuffer> {
 
 impl<'a, S: JsonBuffer> Serializer<'a, S> {
     /// Creates a new serializer over a JSON output buffer.
-    pub fn new(buf: &mut S) -> Serializer<S> {
+    pub fn new(buf: &mut S) -> Serializer<'_, S> {
         Serializer { buf }
     }
 
@@ -125,7 +198,7 @@ impl<'a, S: JsonBuffer> Serializer<'a, S> {
     /// arr.add("countrymen");
     /// arr.end();
     /// ```
-    pub fn array(&mut self) -> ArrayWriter<S> {
+    pub fn array(&mut self) -> ArrayWriter<'_, S> {
         ArrayWriter::start(self.buf)
     }
 
@@ -144,10 +217,29 @@ impl<'a, S: JsonBuffer> Serializer<'a, S> {
     /// obj.field("meow_decibels", 45);
     /// obj.end();
     /// ```
-    pub fn object(&mut self) -> ObjectWriter<S> {
+    pub fn object(&mut self) -> ObjectWriter<'_, S> {
         ObjectWriter::start(self.buf)
     }
 
+    /// Starts serialization of a string, which can then be written in pieces.
+    /// # Examples
+    ///
+    /// ```
+    /// use nyoom_json::Serializer;
+    ///
+    /// let mut out = String::new();
+    /// let mut ser = Serializer::new(&mut out);
+    ///
+    /// let mut s = ser.string();
+    /// s.push_str("mew");
+    /// s.push('!');
+    /// s.end();
+    /// # assert_eq!(out, r#""mew!""#);
+    /// ```
+    pub fn string(&mut self) -> StringWriter<'_, S> {
+        StringWriter::start(self.buf)
+    }
+
     /// Ends the serializer.
     pub fn end(self) {}
 }
@@ -183,6 +275,13 @@ impl<'a, S: JsonBuffer> SingleValueSerializer<'a, S> {
         core::mem::forget(self);
         w
     }
+
+    pub fn string(mut self) -> StringWriter<'a, S> {
+        let buf = unsafe { ManuallyDrop::<&'a mut S>::take(&mut self.guard) };
+        let w = StringWriter::start(buf);
+        core::mem::forget(self);
+        w
+    }
 }
 
 impl<'a, S: JsonBuffer> Drop for SingleValueSerializer<'a, S> {
@@ -304,7 +403,7 @@ impl<'a, S: JsonBuffer> ArrayWriter<'a, S> {
     ///
     /// arr.end();
     /// ```
-    pub fn add_object(&mut self) -> ObjectWriter<S> {
+    pub fn add_object(&mut self) -> ObjectWriter<'_, S> {
         self.comma();
         ObjectWriter::start(self.buf)
     }
@@ -327,11 +426,37 @@ impl<'a, S: JsonBuffer> ArrayWriter<'a, S> {
     ///
     /// arr.end();
     /// ```
-    pub fn add_array(&mut self) -> ArrayWriter<S> {
+    pub fn add_array(&mut self) -> ArrayWriter<'_, S> {
         self.comma();
         ArrayWriter::start(self.buf)
     }
 
+    /// Adds a JSON string to this array, which can then be written in pieces.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use nyoom_json::Serializer;
+    /// #
+    /// # let mut out = String::new();
+    /// # let mut ser = Serializer::new(&mut out);
+    /// #
+    /// let mut arr = ser.array();
+    ///
+    /// let mut purr = arr.add_string();
+    /// for _ in 0..3 {
+    ///     purr.push_str("prr");
+    /// }
+    /// purr.end();
+    ///
+    /// arr.end();
+    /// # assert_eq!(out, r#"["prrprrprr"]"#);
+    /// ```
+    pub fn add_string(&mut self) -> StringWriter<'_, S> {
+        self.comma();
+        StringWriter::start(self.buf)
+    }
+
     /// Finishes out the array. Equivalent to drop(arr);
     pub fn end(self) {}
 }
@@ -342,26 +467,6 @@ impl<S: JsonBuffer> Drop for ArrayWriter<'_, S> {
     }
 }
 
-/// A key for a JSON object's field.
-#[sealed]
-pub trait Key {
-    fn write<S: JsonBuffer>(self, out: &mut S);
-}
-
-#[sealed]
-impl Key for UnescapedStr<'_> {
-    fn write<S: JsonBuffer>(self, out: &mut S) {
-        self.write_to_json(out)
-    }
-}
-
-#[sealed]
-impl<T: AsRef<str>> Key for T {
-    fn write<S: JsonBuffer>(self, out: &mut S) {
-        self.as_ref().write_to_json(out)
-    }
-}
-
 /// A serializer for a JSON object.
 pub struct ObjectWriter<'a, S: JsonBuffer> {
     buf: &'a mut S,
@@ -369,7 +474,7 @@ pub struct ObjectWriter<'a, S: JsonBuffer> {
 }
 
 impl<'a, S: JsonBuffer> ObjectWriter<'a, S> {
-    fn start(buf: &'a mut S) -> ObjectWriter<S> {
+    fn start(buf: &'a mut S) -> ObjectWriter<'a, S> {
         buf.push('{');
         ObjectWriter {
             buf,
@@ -389,8 +494,7 @@ impl<'a, S: JsonBuffer> ObjectWriter<'a, S> {
 
     fn key<K: Key>(&mut self, key: K) {
         self.comma();
-        key.write(&mut self.buf);
-        self.buf.push(':');
+        key.write_key(KeySerializer::new(self.buf));
     }
 
     /// Adds a field to this object.
@@ -413,6 +517,139 @@ impl<'a, S: JsonBuffer> ObjectWriter<'a, S> {
         val.write_to_json(self.buf);
     }
 
+    /// Adds a field to this object if `val` is `Some`, and skips it entirely otherwise.
+    ///
+    /// # Examples
+    /// ```
+    /// # use nyoom_json::Serializer;
+    /// #
+    /// # let mut out = String::new();
+    /// # let mut ser = Serializer::new(&mut out);
+    /// #
+    /// let mut obj = ser.object();
+    /// obj.optional_field("nickname", Some("beans"));
+    /// obj.optional_field("owner", None::<&str>);
+    /// obj.end();
+    /// # assert_eq!(out, r#"{"nickname":"beans"}"#);
+    /// ```
+    pub fn optional_field<K: Key, V: WriteToJson<S>>(&mut self, key: K, val: Option<V>) {
+        if let Some(val) = val {
+            self.field(key, val);
+        }
+    }
+
+    /// Adds a field to this object only if `keep` returns true for its value.
+    ///
+    /// # Examples
+    /// ```
+    /// # use nyoom_json::Serializer;
+    /// #
+    /// # let mut out = String::new();
+    /// # let mut ser = Serializer::new(&mut out);
+    /// #
+    /// let mut obj = ser.object();
+    /// obj.field_if("lives", 9, |lives| *lives > 0);
+    /// obj.field_if("name", "", |name| !name.is_empty());
+    /// obj.end();
+    /// # assert_eq!(out, r#"{"lives":9}"#);
+    /// ```
+    pub fn field_if<K, V, F>(&mut self, key: K, val: V, keep: F)
+    where
+        K: Key,
+        V: WriteToJson<S>,
+        F: FnOnce(&V) -> bool,
+    {
+        if keep(&val) {
+            self.field(key, val);
+        }
+    }
+
+    /// Adds a field to this object unless its value is equal to the type's default.
+    ///
+    /// # Examples
+    /// ```
+    /// # use nyoom_json::Serializer;
+    /// #
+    /// # let mut out = String::new();
+    /// # let mut ser = Serializer::new(&mut out);
+    /// #
+    /// let mut obj = ser.object();
+    /// obj.non_default_field("hunger", 0);
+    /// obj.non_default_field("sleepy", true);
+    /// obj.end();
+    /// # assert_eq!(out, r#"{"sleepy":true}"#);
+    /// ```
+    pub fn non_default_field<K, V>(&mut self, key: K, val: V)
+    where
+        K: Key,
+        V: WriteToJson<S> + Default + PartialEq,
+    {
+        if val != V::default() {
+            self.field(key, val);
+        }
+    }
+
+    /// Adds an array field to this object from an iterator, skipping the field entirely if the iterator is empty.
+    ///
+    /// # Examples
+    /// ```
+    /// # use nyoom_json::Serializer;
+    /// #
+    /// # let mut out = String::new();
+    /// # let mut ser = Serializer::new(&mut out);
+    /// #
+    /// let mut obj = ser.object();
+    /// obj.nonempty_array_field("toys", ["mouse", "ball"]);
+    /// obj.nonempty_array_field("enemies", Vec::<&str>::new());
+    /// obj.end();
+    /// # assert_eq!(out, r#"{"toys":["mouse","ball"]}"#);
+    /// ```
+    pub fn nonempty_array_field<K, I>(&mut self, key: K, vals: I)
+    where
+        K: Key,
+        I: IntoIterator,
+        I::Item: WriteToJson<S>,
+    {
+        let mut vals = vals.into_iter();
+        if let Some(first) = vals.next() {
+            let mut arr = self.array_field(key);
+            arr.add(first);
+            arr.extend(vals);
+        }
+    }
+
+    /// Adds an object field to this object from an iterator of key-value pairs, skipping the field entirely if the iterator is empty.
+    ///
+    /// # Examples
+    /// ```
+    /// # use nyoom_json::Serializer;
+    /// #
+    /// # let mut out = String::new();
+    /// # let mut ser = Serializer::new(&mut out);
+    /// #
+    /// let mut obj = ser.object();
+    /// obj.nonempty_object_field("vaccines", [("rabies", true), ("fvrcp", false)]);
+    /// obj.nonempty_object_field("allergies", Vec::<(&str, bool)>::new());
+    /// obj.end();
+    /// # assert_eq!(out, r#"{"vaccines":{"rabies":true,"fvrcp":false}}"#);
+    /// ```
+    pub fn nonempty_object_field<K, I, FK, FV>(&mut self, key: K, fields: I)
+    where
+        K: Key,
+        I: IntoIterator<Item = (FK, FV)>,
+        FK: Key,
+        FV: WriteToJson<S>,
+    {
+        let mut fields = fields.into_iter();
+        if let Some((first_key, first_val)) = fields.next() {
+            let mut obj = self.object_field(key);
+            obj.field(first_key, first_val);
+            for (k, v) in fields {
+                obj.field(k, v);
+            }
+        }
+    }
+
     /// Adds an arbitrary JSON object to this object.
     ///
     /// # Arguments
@@ -467,7 +704,7 @@ impl<'a, S: JsonBuffer> ObjectWriter<'a, S> {
     ///
     /// obj.end();
     /// ```
-    pub fn object_field<K: Key>(&mut self, key: K) -> ObjectWriter<S> {
+    pub fn object_field<K: Key>(&mut self, key: K) -> ObjectWriter<'_, S> {
         self.key(key);
         ObjectWriter::start(self.buf)
     }
@@ -496,11 +733,43 @@ impl<'a, S: JsonBuffer> ObjectWriter<'a, S> {
     ///
     /// arr.end();
     /// ```
-    pub fn array_field<K: Key>(&mut self, key: K) -> ArrayWriter<S> {
+    pub fn array_field<K: Key>(&mut self, key: K) -> ArrayWriter<'_, S> {
         self.key(key);
         ArrayWriter::start(self.buf)
     }
 
+    /// Adds a JSON string field to this object, which can then be written in pieces.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use nyoom_json::Serializer;
+    /// use std::fmt::Write;
+    /// #
+    /// # let mut out = String::new();
+    /// # let mut ser = Serializer::new(&mut out);
+    /// #
+    /// let mut obj = ser.object();
+    ///
+    /// let mut log = obj.string_field("log");
+    /// log.push_str("ate \"fish\"\n");
+    /// write!(log, "napped for {} hours", 14).unwrap();
+    /// log.end();
+    ///
+    /// obj.end();
+    /// # assert_eq!(out, r#"{"log":"ate \"fish\"\nnapped for 14 hours"}"#);
+    /// ```
+    pub fn string_field<K: Key>(&mut self, key: K) -> StringWriter<'_, S> {
+        self.key(key);
+        StringWriter::start(self.buf)
+    }
+
+    /// Adds all of `fields`' fields to this object, rather than nesting them under a key. See [WriteFields](WriteFields).
+    #[inline]
+    pub fn flatten<F: WriteFields<S> + ?Sized>(&mut self, fields: &F) {
+        fields.write_fields(self)
+    }
+
     pub fn end(self) {}
 }
 
@@ -509,3 +778,46 @@ impl<S: JsonBuffer> Drop for ObjectWriter<'_, S> {
         self.buf.push('}');
     }
 }
+
+/// Serializer for a JSON string that is written in pieces. Everything pushed into it is escaped.
+///
+/// It also implements [fmt::Write](core::fmt::Write), so it can be used with `write!`.
+pub struct StringWriter<'a, S: JsonBuffer> {
+    buf: &'a mut S,
+}
+
+impl<'a, S: JsonBuffer> StringWriter<'a, S> {
+    fn start(buf: &'a mut S) -> StringWriter<'a, S> {
+        buf.push('"');
+        StringWriter { buf }
+    }
+
+    /// Adds a piece of text to this string.
+    #[inline(always)]
+    pub fn push_str(&mut self, s: &str) {
+        escape_str(s, self.buf)
+    }
+
+    /// Adds a single character to this string.
+    #[inline(always)]
+    pub fn push(&mut self, c: char) {
+        escape_str(c.encode_utf8(&mut [0; 4]), self.buf)
+    }
+
+    /// Finishes out the string. Equivalent to drop(s);
+    pub fn end(self) {}
+}
+
+impl<S: JsonBuffer> fmt::Write for StringWriter<'_, S> {
+    #[inline(always)]
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.push_str(s);
+        Ok(())
+    }
+}
+
+impl<S: JsonBuffer> Drop for StringWriter<'_, S> {
+    fn drop(&mut self) {
+        self.buf.push('"');
+    }
+}
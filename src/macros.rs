@@ -0,0 +1,191 @@
+/// Writes JSON-like syntax straight into a serializer, expanding into the same `object()`/`field()`/`array()`/`add()` calls you'd write by hand. Nothing is allocated along the way.
+///
+/// The first argument is anything with `object()`, `array()` and `write()` methods, like a [Serializer](crate::Serializer) or [SingleValueSerializer](crate::SingleValueSerializer).
+///
+/// Values are `null`, nested objects and arrays, or any Rust expression that is [WriteToJson](crate::WriteToJson). Expressions that start with `{` or `[` need to be wrapped in parentheses.
+/// String literal keys are checked and rendered at compile time, like the [key!](crate::key) macro; any other [Key](crate::Key) can be used by wrapping it in parentheses.
+/// # Examples
+/// ```
+/// use nyoom_json::{json, Serializer};
+///
+/// let name = "mittens";
+/// let toys = ["mouse", "ball"];
+/// let owner_key = String::from("owner");
+///
+/// let mut out = String::new();
+/// let mut ser = Serializer::new(&mut out);
+///
+/// json!(ser, {
+///     "name": name,
+///     "lives": 9,
+///     "illness": null,
+///     "toys": &toys,
+///     "bed": { "cozy": true, "size": [40, 20] },
+///     "friends": [{ "name": "beans" }, null, ["nested"]],
+///     (owner_key): "emily",
+/// });
+///
+/// assert_eq!(
+///     out,
+///     r#"{"name":"mittens","lives":9,"illness":null,"toys":["mouse","ball"],"bed":{"cozy":true,"size":[40,20]},"friends":[{"name":"beans"},null,["nested"]],"owner":"emily"}"#
+/// );
+/// ```
+///
+/// ```compile_fail
+/// # use nyoom_json::{json, Serializer};
+/// # let mut out = String::new();
+/// # let mut ser = Serializer::new(&mut out);
+/// json!(ser, { "needs\nescaping": true });
+/// ```
+#[macro_export]
+macro_rules! json {
+    // objects, as `key: value` pairs
+    (@object $obj:ident) => {};
+    (@object $obj:ident $key:literal : $($rest:tt)*) => {
+        $crate::json!(@field $obj ($crate::key!($key)) $($rest)*)
+    };
+    (@object $obj:ident ($key:expr) : $($rest:tt)*) => {
+        $crate::json!(@field $obj ($key) $($rest)*)
+    };
+
+    (@field $obj:ident ($key:expr) null $(, $($rest:tt)*)?) => {
+        $obj.field($key, $crate::Null);
+        $crate::json!(@object $obj $($($rest)*)?);
+    };
+    (@field $obj:ident ($key:expr) { $($body:tt)* } $(, $($rest:tt)*)?) => {
+        {
+            let mut obj = $obj.object_field($key);
+            $crate::json!(@object obj $($body)*);
+        }
+        $crate::json!(@object $obj $($($rest)*)?);
+    };
+    (@field $obj:ident ($key:expr) [ $($body:tt)* ] $(, $($rest:tt)*)?) => {
+        {
+            let mut arr = $obj.array_field($key);
+            $crate::json!(@array arr $($body)*);
+        }
+        $crate::json!(@object $obj $($($rest)*)?);
+    };
+    (@field $obj:ident ($key:expr) $value:expr $(, $($rest:tt)*)?) => {
+        $obj.field($key, $value);
+        $crate::json!(@object $obj $($($rest)*)?);
+    };
+
+    // arrays, as comma separated values
+    (@array $arr:ident) => {};
+    (@array $arr:ident null $(, $($rest:tt)*)?) => {
+        $arr.add($crate::Null);
+        $crate::json!(@array $arr $($($rest)*)?);
+    };
+    (@array $arr:ident { $($body:tt)* } $(, $($rest:tt)*)?) => {
+        {
+            let mut obj = $arr.add_object();
+            $crate::json!(@object obj $($body)*);
+        }
+        $crate::json!(@array $arr $($($rest)*)?);
+    };
+    (@array $arr:ident [ $($body:tt)* ] $(, $($rest:tt)*)?) => {
+        {
+            let mut arr = $arr.add_array();
+            $crate::json!(@array arr $($body)*);
+        }
+        $crate::json!(@array $arr $($($rest)*)?);
+    };
+    (@array $arr:ident $value:expr $(, $($rest:tt)*)?) => {
+        $arr.add($value);
+        $crate::json!(@array $arr $($($rest)*)?);
+    };
+
+    // a single value, at the top level
+    (@value ($ser:expr) null) => {
+        $ser.write($crate::Null)
+    };
+    (@value ($ser:expr) { $($body:tt)* }) => {{
+        let mut obj = $ser.object();
+        $crate::json!(@object obj $($body)*);
+    }};
+    (@value ($ser:expr) [ $($body:tt)* ]) => {{
+        let mut arr = $ser.array();
+        $crate::json!(@array arr $($body)*);
+    }};
+    (@value ($ser:expr) $value:expr) => {
+        $ser.write($value)
+    };
+
+    ($ser:expr, $($value:tt)+) => {
+        $crate::json!(@value ($ser) $($value)+)
+    };
+}
+
+/// Turns JSON written inline into a minified `&'static str`, checked at compile time.
+///
+/// Only literals are allowed; strings are written as Rust string literals, so their escapes must also be valid JSON escapes. Use [json_raw!](crate::json_raw) to get a [RawJson](crate::RawJson) you can splice into a writer.
+/// # Examples
+/// ```
+/// use nyoom_json::json_str;
+///
+/// const NOT_FOUND: &str = json_str!({
+///     "error": "not found",
+///     "code": 404,
+///     "retry": [false, null, -1.5e3],
+///     "hint": "check the \"id\"\n"
+/// });
+///
+/// assert_eq!(
+///     NOT_FOUND,
+///     r#"{"error":"not found","code":404,"retry":[false,null,-1.5e3],"hint":"check the \"id\"\n"}"#
+/// );
+/// ```
+///
+/// ```compile_fail
+/// const BAD: &str = nyoom_json::json_str!({ "missing": });
+/// ```
+///
+/// ```compile_fail
+/// const BAD: &str = nyoom_json::json_str!([1 2]);
+/// ```
+///
+/// ```compile_fail
+/// const BAD: &str = nyoom_json::json_str!([01, 1.]);
+/// ```
+///
+/// ```compile_fail
+/// const BAD: &str = nyoom_json::json_str!([- - 1]);
+/// ```
+#[macro_export]
+macro_rules! json_str {
+    ($($json:tt)+) => {{
+        const SOURCE: &[u8] = stringify!($($json)+).as_bytes();
+        const LEN: usize = $crate::__private::minified_len(SOURCE);
+        const MINIFIED: [u8; LEN] =
+            $crate::__private::minify::<LEN, 0>(SOURCE, $crate::__private::Holes::Keep).text;
+        const JSON: &str = $crate::__private::minified_str(&MINIFIED);
+        JSON
+    }};
+}
+
+/// Turns JSON written inline into a [RawJson](crate::RawJson), minified and checked at compile time. See [json_str!](crate::json_str).
+/// # Examples
+/// ```
+/// use nyoom_json::{json_raw, RawJson, Serializer};
+///
+/// const DEFAULT_SETTINGS: RawJson<'static> = json_raw!({ "theme": "dark", "volume": 11 });
+///
+/// let mut out = String::new();
+/// let mut ser = Serializer::new(&mut out);
+///
+/// let mut obj = ser.object();
+/// obj.field("user", "mittens");
+/// obj.field("settings", DEFAULT_SETTINGS);
+/// obj.field("history", json_raw!([]));
+/// obj.end();
+///
+/// assert_eq!(out, r#"{"user":"mittens","settings":{"theme":"dark","volume":11},"history":[]}"#);
+/// ```
+#[macro_export]
+macro_rules! json_raw {
+    ($($json:tt)+) => {{
+        const RAW: $crate::RawJson<'static> = $crate::RawJson::from_static($crate::json_str!($($json)+));
+        RAW
+    }};
+}
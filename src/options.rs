@@ -0,0 +1,106 @@
+use crate::{DurationFormat, JsonBuffer, TimestampFormat};
+
+/// Options that apply to everything written into a buffer.
+///
+/// Options are carried by the [JsonBuffer](JsonBuffer) itself, so the easiest way to set them for a whole document is to wrap the output in [WithOptions](WithOptions).
+/// # Examples
+/// ```
+/// use nyoom_json::{Options, Serializer, WithOptions};
+///
+/// let mut out = String::new();
+/// let mut buf = WithOptions::new(&mut out, Options::new().large_ints_as_strings(true));
+/// let mut ser = Serializer::new(&mut buf);
+///
+/// let mut arr = ser.array();
+/// arr.add(9_007_199_254_740_991u64);
+/// arr.add(9_007_199_254_740_992u64);
+/// arr.end();
+///
+/// assert_eq!(out, r#"[9007199254740991,"9007199254740992"]"#);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Options {
+    /// Write integers that JavaScript can't represent exactly (anything outside of ±(2^53 - 1)) as quoted decimal strings.
+    pub large_ints_as_strings: bool,
+    /// How [Duration](core::time::Duration)s are written.
+    pub duration_format: DurationFormat,
+    /// How points in time, like `SystemTime`s, are written.
+    pub timestamp_format: TimestampFormat,
+}
+
+impl Options {
+    /// The default options, as used by plain buffers like `String`.
+    pub const DEFAULT: Options = Options {
+        large_ints_as_strings: false,
+        duration_format: DurationFormat::Seconds,
+        timestamp_format: TimestampFormat::Rfc3339,
+    };
+
+    /// Creates the default set of options.
+    #[inline(always)]
+    pub const fn new() -> Options {
+        Options::DEFAULT
+    }
+
+    /// Sets whether integers outside of JavaScript's safe integer range are written as strings.
+    #[inline(always)]
+    pub const fn large_ints_as_strings(mut self, enabled: bool) -> Options {
+        self.large_ints_as_strings = enabled;
+        self
+    }
+
+    /// Sets how [Duration](core::time::Duration)s are written.
+    #[inline(always)]
+    pub const fn duration_format(mut self, format: DurationFormat) -> Options {
+        self.duration_format = format;
+        self
+    }
+
+    /// Sets how points in time, like `SystemTime`s, are written.
+    #[inline(always)]
+    pub const fn timestamp_format(mut self, format: TimestampFormat) -> Options {
+        self.timestamp_format = format;
+        self
+    }
+}
+
+/// A [JsonBuffer](JsonBuffer) wrapper that applies a set of [Options](Options) to everything written through it.
+pub struct WithOptions<S: JsonBuffer> {
+    buf: S,
+    options: Options,
+}
+
+impl<S: JsonBuffer> WithOptions<S> {
+    /// Wraps a buffer, applying `options` to everything written into it.
+    pub fn new(buf: S, options: Options) -> WithOptions<S> {
+        WithOptions { buf, options }
+    }
+
+    /// Unwraps the inner buffer.
+    pub fn into_inner(self) -> S {
+        self.buf
+    }
+}
+
+impl<S: JsonBuffer> JsonBuffer for WithOptions<S> {
+    #[inline(always)]
+    fn push(&mut self, c: char) {
+        self.buf.push(c)
+    }
+
+    #[inline(always)]
+    fn push_str(&mut self, s: &str) {
+        self.buf.push_str(s)
+    }
+
+    #[inline(always)]
+    fn reserve(&mut self, l: usize) {
+        self.buf.reserve(l)
+    }
+
+    #[inline(always)]
+    fn options(&self) -> Options {
+        self.options
+    }
+}
@@ -0,0 +1,148 @@
+use crate::{validate, JsonBuffer, WriteToJson, WriteToJsonRef};
+
+/// A number that is written into JSON verbatim, without ever going through a float or integer type.
+///
+/// Useful for arbitrary-precision decimals (money, measurements) that come from somewhere as text.
+/// # Examples
+/// ```
+/// use nyoom_json::{RawNumber, Serializer};
+///
+/// let mut out = String::new();
+/// let mut ser = Serializer::new(&mut out);
+///
+/// let mut obj = ser.object();
+/// obj.field("price", RawNumber::try_create("1234567890.0123456789").unwrap());
+/// obj.field("avogadro", RawNumber::create("6.02214076e23"));
+/// obj.end();
+///
+/// assert_eq!(out, r#"{"price":1234567890.0123456789,"avogadro":6.02214076e23}"#);
+/// assert!(RawNumber::try_create("01.5").is_none());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct RawNumber<'a>(&'a str);
+
+impl<'a> RawNumber<'a> {
+    /// Creates a raw number without checking it. You should only use this if you're *absolutely* sure `val` is a valid JSON number; it's only checked in debug builds.
+    #[inline(always)]
+    pub fn create(val: &'a str) -> RawNumber<'a> {
+        debug_assert!(
+            validate::is_number(val.as_bytes()),
+            "string is not a valid JSON number!"
+        );
+
+        RawNumber(val)
+    }
+
+    /// Creates a raw number, returning `None` if `val` is not a valid JSON number.
+    #[inline]
+    pub fn try_create(val: &'a str) -> Option<RawNumber<'a>> {
+        match validate::is_number(val.as_bytes()) {
+            true => Some(RawNumber(val)),
+            false => None,
+        }
+    }
+
+    /// The number's text.
+    #[inline(always)]
+    pub fn as_str(&self) -> &'a str {
+        self.0
+    }
+}
+
+impl<'a, S: JsonBuffer> WriteToJson<S> for RawNumber<'a> {
+    #[inline(always)]
+    fn write_to_json(self, out: &mut S) {
+        out.push_str(self.0)
+    }
+}
+
+impl<'a, S: JsonBuffer> WriteToJsonRef<S> for RawNumber<'a> {
+    #[inline(always)]
+    fn write_to_json_ref(&self, out: &mut S) {
+        out.push_str(self.0)
+    }
+}
+
+/// A pre-serialized piece of JSON that is written out verbatim.
+///
+/// Use this to splice cached or otherwise already serialized documents into a larger one.
+/// # Examples
+/// ```
+/// use nyoom_json::{RawJson, Serializer};
+///
+/// let cached = r#"{"name":"mittens","lives":9}"#;
+///
+/// let mut out = String::new();
+/// let mut ser = Serializer::new(&mut out);
+///
+/// let mut arr = ser.array();
+/// arr.add(RawJson::try_create(cached).unwrap());
+/// arr.add(RawJson::create("[1,2,3]"));
+/// arr.end();
+///
+/// assert_eq!(out, r#"[{"name":"mittens","lives":9},[1,2,3]]"#);
+/// assert!(RawJson::try_create(r#"{"name":}"#).is_none());
+/// assert!(RawJson::try_create("1 2").is_none());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct RawJson<'a>(&'a str);
+
+impl<'a> RawJson<'a> {
+    /// Creates a raw JSON fragment without checking it. You should only use this if you're *absolutely* sure `val` is exactly one valid JSON value; it's only checked in debug builds.
+    #[inline(always)]
+    pub fn create(val: &'a str) -> RawJson<'a> {
+        debug_assert!(
+            validate::is_value(val.as_bytes()),
+            "string is not a single valid JSON value!"
+        );
+
+        RawJson(val)
+    }
+
+    /// Creates a raw JSON fragment that is checked at compile time, when used in a `const`. The [json_raw!](crate::json_raw) macro lets you write the JSON inline instead.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `val` is not exactly one well-formed JSON value.
+    pub const fn from_static(val: &'static str) -> RawJson<'static> {
+        assert!(
+            validate::is_value(val.as_bytes()),
+            "string is not a single valid JSON value!"
+        );
+
+        RawJson(val)
+    }
+
+    /// Creates a raw JSON fragment, returning `None` if `val` is not exactly one well-formed JSON value.
+    ///
+    /// Whitespace around the value is allowed. Arrays and objects nested more than 128 levels deep are rejected.
+    #[inline]
+    pub fn try_create(val: &'a str) -> Option<RawJson<'a>> {
+        match validate::is_value(val.as_bytes()) {
+            true => Some(RawJson(val)),
+            false => None,
+        }
+    }
+
+    /// The fragment's text.
+    #[inline(always)]
+    pub fn as_str(&self) -> &'a str {
+        self.0
+    }
+}
+
+impl<'a, S: JsonBuffer> WriteToJson<S> for RawJson<'a> {
+    #[inline(always)]
+    fn write_to_json(self, out: &mut S) {
+        out.push_str(self.0)
+    }
+}
+
+impl<'a, S: JsonBuffer> WriteToJsonRef<S> for RawJson<'a> {
+    #[inline(always)]
+    fn write_to_json_ref(&self, out: &mut S) {
+        out.push_str(self.0)
+    }
+}
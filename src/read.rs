@@ -0,0 +1,192 @@
+use std::io::{self, Read};
+
+use crate::{escape::escape_str, JsonBuffer, StringWriter, WriteToJson};
+
+/// How many bytes are read (and buffered on the stack) at a time.
+const CHUNK_SIZE: usize = 8 * 1024;
+
+/// What to do with bytes that aren't valid UTF-8 when reading text into a JSON string.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum InvalidUtf8 {
+    /// Replace each invalid sequence with U+FFFD REPLACEMENT CHARACTER.
+    #[default]
+    Replace,
+    /// Leave invalid sequences out.
+    Skip,
+    /// Stop reading with an [InvalidData](std::io::ErrorKind::InvalidData) error.
+    Error,
+}
+
+impl<S: JsonBuffer> StringWriter<'_, S> {
+    /// Reads all of `reader` into this string in bounded chunks, escaping it along the way. Returns the number of bytes read.
+    ///
+    /// UTF-8 sequences that are split across reads are put back together; invalid ones are handled according to `invalid`.
+    /// On error, everything read up until that point stays in the string.
+    /// # Examples
+    /// ```
+    /// use nyoom_json::{InvalidUtf8, Serializer};
+    ///
+    /// let log: &[u8] = b"woke up\nate \xF0\x9F\x90\x9F\nnapped \xFF";
+    ///
+    /// let mut out = String::new();
+    /// let mut ser = Serializer::new(&mut out);
+    ///
+    /// let mut obj = ser.object();
+    /// let mut s = obj.string_field("log");
+    /// s.read_from(log, InvalidUtf8::Replace).unwrap();
+    /// s.end();
+    /// obj.end();
+    ///
+    /// assert_eq!(out, r#"{"log":"woke up\nate 🐟\nnapped �"}"#);
+    /// ```
+    pub fn read_from<R: Read>(&mut self, reader: R, invalid: InvalidUtf8) -> io::Result<u64> {
+        copy_escaped(reader, invalid, self.buf)
+    }
+}
+
+fn copy_escaped<R: Read, S: JsonBuffer>(
+    mut reader: R,
+    invalid: InvalidUtf8,
+    out: &mut S,
+) -> io::Result<u64> {
+    let mut buf = [0u8; CHUNK_SIZE];
+    let mut carried = 0;
+    let mut total = 0;
+
+    loop {
+        let read = match reader.read(&mut buf[carried..]) {
+            Ok(read) => read,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+
+        total += read as u64;
+        let eof = read == 0;
+        let len = carried + read;
+        let mut start = 0;
+        carried = 0;
+
+        while start < len {
+            match core::str::from_utf8(&buf[start..len]) {
+                Ok(text) => {
+                    escape_str(text, out);
+                    break;
+                }
+                Err(e) => {
+                    let valid_end = start + e.valid_up_to();
+                    escape_str(
+                        unsafe { core::str::from_utf8_unchecked(&buf[start..valid_end]) },
+                        out,
+                    );
+
+                    match e.error_len() {
+                        Some(bad) => {
+                            invalid_sequence(invalid, out)?;
+                            start = valid_end + bad;
+                        }
+                        // the chunk ends partway through a character, so save the start of it for the next read
+                        None if !eof => {
+                            buf.copy_within(valid_end..len, 0);
+                            carried = len - valid_end;
+                            break;
+                        }
+                        None => {
+                            invalid_sequence(invalid, out)?;
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+
+        if eof {
+            return Ok(total);
+        }
+    }
+}
+
+fn invalid_sequence<S: JsonBuffer>(invalid: InvalidUtf8, out: &mut S) -> io::Result<()> {
+    match invalid {
+        InvalidUtf8::Replace => {
+            out.push(char::REPLACEMENT_CHARACTER);
+            Ok(())
+        }
+        InvalidUtf8::Skip => Ok(()),
+        InvalidUtf8::Error => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "stream did not contain valid UTF-8",
+        )),
+    }
+}
+
+/// A reader whose contents are written as a JSON string, streamed in bounded chunks rather than read into memory up front.
+///
+/// Since writing a value can't fail, any error simply ends the string early. Use [report_errors](ReadStr::report_errors) to find out about it, or [StringWriter::read_from](StringWriter::read_from) to get the error back directly.
+/// # Examples
+/// ```
+/// use nyoom_json::{InvalidUtf8, ReadStr, Serializer};
+///
+/// let stdout: &[u8] = b"mew\tmew\xC3";
+/// let mut error = None;
+///
+/// let mut out = String::new();
+/// let mut ser = Serializer::new(&mut out);
+///
+/// let mut obj = ser.object();
+/// obj.field("stdout", ReadStr::new(stdout).invalid_utf8(InvalidUtf8::Skip));
+/// obj.field(
+///     "strict",
+///     ReadStr::new(stdout)
+///         .invalid_utf8(InvalidUtf8::Error)
+///         .report_errors(&mut error),
+/// );
+/// obj.end();
+///
+/// assert_eq!(out, r#"{"stdout":"mew\tmew","strict":"mew\tmew"}"#);
+/// assert!(error.is_some());
+/// ```
+pub struct ReadStr<'e, R> {
+    reader: R,
+    invalid: InvalidUtf8,
+    error: Option<&'e mut Option<io::Error>>,
+}
+
+impl<R: Read> ReadStr<'static, R> {
+    /// Wraps a reader, replacing invalid UTF-8 and ignoring errors.
+    pub fn new(reader: R) -> ReadStr<'static, R> {
+        ReadStr {
+            reader,
+            invalid: InvalidUtf8::Replace,
+            error: None,
+        }
+    }
+}
+
+impl<'e, R: Read> ReadStr<'e, R> {
+    /// Sets what to do with invalid UTF-8.
+    pub fn invalid_utf8(mut self, invalid: InvalidUtf8) -> ReadStr<'e, R> {
+        self.invalid = invalid;
+        self
+    }
+
+    /// Stores the error that ended the string early, if any, in `error`.
+    pub fn report_errors<'f>(self, error: &'f mut Option<io::Error>) -> ReadStr<'f, R> {
+        ReadStr {
+            reader: self.reader,
+            invalid: self.invalid,
+            error: Some(error),
+        }
+    }
+}
+
+impl<S: JsonBuffer, R: Read> WriteToJson<S> for ReadStr<'_, R> {
+    fn write_to_json(self, out: &mut S) {
+        out.push('"');
+        let result = copy_escaped(self.reader, self.invalid, out);
+        out.push('"');
+
+        if let (Err(e), Some(error)) = (result, self.error) {
+            *error = Some(e);
+        }
+    }
+}
@@ -0,0 +1,675 @@
+use alloc::string::{String, ToString};
+use core::fmt::{self, Write};
+
+use serde::ser::{
+    self, Impossible, Serialize, SerializeMap, SerializeSeq, SerializeStruct,
+    SerializeStructVariant, SerializeTuple, SerializeTupleStruct, SerializeTupleVariant,
+};
+
+use crate::{
+    ArrayWriter, JsonBuffer, KeySerializer, ObjectWriter, RawNumber, SingleValueSerializer,
+    WriteToJson, WriteToJsonRef,
+};
+
+/// An error raised while writing a [Serialize](serde::Serialize) value, either by the value itself or because a map key couldn't be written as a string.
+///
+/// Serialization is never rolled back, but output stays valid JSON: whatever failed to be written is replaced by `null`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SerdeError(String);
+
+impl fmt::Display for SerdeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl ser::StdError for SerdeError {}
+
+impl ser::Error for SerdeError {
+    fn custom<T: fmt::Display>(msg: T) -> SerdeError {
+        SerdeError(msg.to_string())
+    }
+}
+
+fn key_must_be_a_string() -> SerdeError {
+    SerdeError(String::from("key must be a string"))
+}
+
+/// Any [Serialize](serde::Serialize) value, written the same way serde_json would write it.
+///
+/// Since writing a value can't fail, errors are swallowed, and output stays valid JSON with `null` in place of whatever failed; a map key that can't be written as a string (which serde_json refuses) leaves a `"":null` entry behind.
+/// Use [report_errors](Serde::report_errors) to find out about them, or serialize into a [SingleValueSerializer](SingleValueSerializer) directly to get them back, as it implements [serde::Serializer](serde::Serializer).
+/// # Examples
+/// ```
+/// use nyoom_json::{Serde, Serializer};
+/// use serde::Serialize;
+///
+/// #[derive(Serialize)]
+/// enum Mood {
+///     Sleepy { hours: u8 },
+/// }
+///
+/// #[derive(Serialize)]
+/// struct Cat {
+///     name: &'static str,
+///     mood: Mood,
+/// }
+///
+/// let cat = Cat { name: "mittens", mood: Mood::Sleepy { hours: 14 } };
+///
+/// let mut out = String::new();
+/// let mut ser = Serializer::new(&mut out);
+///
+/// let mut arr = ser.array();
+/// arr.add(Serde(&cat));
+/// arr.add_complex(|ser| cat.serialize(ser)).unwrap();
+/// arr.end();
+///
+/// assert_eq!(
+///     out,
+///     r#"[{"name":"mittens","mood":{"Sleepy":{"hours":14}}},{"name":"mittens","mood":{"Sleepy":{"hours":14}}}]"#
+/// );
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct Serde<T>(pub T);
+
+impl<T: Serialize> Serde<T> {
+    /// Stores the error that cut the value short, if any, in `error`.
+    /// # Examples
+    /// ```
+    /// use nyoom_json::{Serde, Serializer};
+    /// use std::collections::BTreeMap;
+    ///
+    /// let by_owner = BTreeMap::from([(("emily", 1), "mittens")]);
+    /// let mut error = None;
+    ///
+    /// let mut out = String::new();
+    /// let mut ser = Serializer::new(&mut out);
+    /// ser.write(Serde(&by_owner).report_errors(&mut error));
+    ///
+    /// assert_eq!(out, r#"{"":null}"#);
+    /// assert_eq!(error.unwrap().to_string(), "key must be a string");
+    /// ```
+    pub fn report_errors(self, error: &mut Option<SerdeError>) -> SerdeReportingErrors<'_, T> {
+        SerdeReportingErrors {
+            value: self.0,
+            error,
+        }
+    }
+}
+
+impl<S: JsonBuffer, T: Serialize> WriteToJsonRef<S> for Serde<T> {
+    #[inline]
+    fn write_to_json_ref(&self, out: &mut S) {
+        let _ = self.0.serialize(SingleValueSerializer::new(out));
+    }
+}
+
+impl<S: JsonBuffer, T: Serialize> WriteToJson<S> for Serde<T> {
+    #[inline(always)]
+    fn write_to_json(self, out: &mut S) {
+        self.write_to_json_ref(out)
+    }
+}
+
+/// A [Serde](Serde) value that stores any error raised while writing it, made by [Serde::report_errors](Serde::report_errors).
+pub struct SerdeReportingErrors<'e, T> {
+    value: T,
+    error: &'e mut Option<SerdeError>,
+}
+
+impl<S: JsonBuffer, T: Serialize> WriteToJson<S> for SerdeReportingErrors<'_, T> {
+    #[inline]
+    fn write_to_json(self, out: &mut S) {
+        if let Err(e) = self.value.serialize(SingleValueSerializer::new(out)) {
+            *self.error = Some(e);
+        }
+    }
+}
+
+impl<'a, S: JsonBuffer> ser::Serializer for SingleValueSerializer<'a, S> {
+    type Ok = ();
+    type Error = SerdeError;
+
+    type SerializeSeq = ArrayWriter<'a, S>;
+    type SerializeTuple = ArrayWriter<'a, S>;
+    type SerializeTupleStruct = ArrayWriter<'a, S>;
+    type SerializeTupleVariant = VariantWriter<'a, S>;
+    type SerializeMap = ObjectWriter<'a, S>;
+    type SerializeStruct = ObjectWriter<'a, S>;
+    type SerializeStructVariant = VariantWriter<'a, S>;
+
+    fn serialize_bool(self, v: bool) -> Result<(), SerdeError> {
+        self.write(v);
+        Ok(())
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<(), SerdeError> {
+        self.write(v);
+        Ok(())
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<(), SerdeError> {
+        self.write(v);
+        Ok(())
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<(), SerdeError> {
+        self.write(v);
+        Ok(())
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<(), SerdeError> {
+        self.write(v);
+        Ok(())
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<(), SerdeError> {
+        self.write(v);
+        Ok(())
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<(), SerdeError> {
+        self.write(v);
+        Ok(())
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<(), SerdeError> {
+        self.write(v);
+        Ok(())
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<(), SerdeError> {
+        self.write(v);
+        Ok(())
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<(), SerdeError> {
+        self.write(v);
+        Ok(())
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<(), SerdeError> {
+        self.write(v);
+        Ok(())
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<(), SerdeError> {
+        self.write_float(v.is_finite(), v);
+        Ok(())
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<(), SerdeError> {
+        self.write_float(v.is_finite(), v);
+        Ok(())
+    }
+
+    fn serialize_char(self, v: char) -> Result<(), SerdeError> {
+        self.write(v);
+        Ok(())
+    }
+
+    fn serialize_str(self, v: &str) -> Result<(), SerdeError> {
+        self.write(v);
+        Ok(())
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<(), SerdeError> {
+        self.array().extend(v);
+        Ok(())
+    }
+
+    fn serialize_none(self) -> Result<(), SerdeError> {
+        self.write(crate::Null);
+        Ok(())
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<(), SerdeError> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<(), SerdeError> {
+        self.write(crate::Null);
+        Ok(())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<(), SerdeError> {
+        self.write(crate::Null);
+        Ok(())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<(), SerdeError> {
+        self.write(variant);
+        Ok(())
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<(), SerdeError> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<(), SerdeError> {
+        let mut obj = self.object();
+        obj.key(variant);
+        value.serialize(SingleValueSerializer::new(obj.buf))
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<ArrayWriter<'a, S>, SerdeError> {
+        Ok(self.array())
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<ArrayWriter<'a, S>, SerdeError> {
+        Ok(self.array())
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<ArrayWriter<'a, S>, SerdeError> {
+        Ok(self.array())
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<VariantWriter<'a, S>, SerdeError> {
+        Ok(VariantWriter::start(self.object(), variant, '[', ']'))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<ObjectWriter<'a, S>, SerdeError> {
+        Ok(self.object())
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<ObjectWriter<'a, S>, SerdeError> {
+        Ok(self.object())
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<VariantWriter<'a, S>, SerdeError> {
+        Ok(VariantWriter::start(self.object(), variant, '{', '}'))
+    }
+
+    fn collect_str<T: fmt::Display + ?Sized>(self, value: &T) -> Result<(), SerdeError> {
+        let _ = write!(self.string(), "{}", value);
+        Ok(())
+    }
+}
+
+macro_rules! impl_serialize_array {
+    ($($trait:ident::$method:ident),*) => {
+        $(
+            impl<S: JsonBuffer> $trait for ArrayWriter<'_, S> {
+                type Ok = ();
+                type Error = SerdeError;
+
+                #[inline]
+                fn $method<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SerdeError> {
+                    self.comma();
+                    value.serialize(SingleValueSerializer::new(self.buf))
+                }
+
+                #[inline(always)]
+                fn end(self) -> Result<(), SerdeError> {
+                    Ok(())
+                }
+            }
+        )*
+    }
+}
+
+impl_serialize_array!(
+    SerializeSeq::serialize_element,
+    SerializeTuple::serialize_element,
+    SerializeTupleStruct::serialize_field
+);
+
+impl<S: JsonBuffer> SerializeMap for ObjectWriter<'_, S> {
+    type Ok = ();
+    type Error = SerdeError;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), SerdeError> {
+        self.comma();
+        key.serialize(KeySerializer::new(self.buf))
+            // the key serializer has already written an empty key, so give it a value too
+            .inspect_err(|_| self.buf.push_str("null"))
+    }
+
+    #[inline]
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SerdeError> {
+        value.serialize(SingleValueSerializer::new(self.buf))
+    }
+
+    #[inline(always)]
+    fn end(self) -> Result<(), SerdeError> {
+        Ok(())
+    }
+}
+
+impl<S: JsonBuffer> SerializeStruct for ObjectWriter<'_, S> {
+    type Ok = ();
+    type Error = SerdeError;
+
+    #[inline]
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), SerdeError> {
+        self.key(key);
+        value.serialize(SingleValueSerializer::new(self.buf))
+    }
+
+    #[inline(always)]
+    fn end(self) -> Result<(), SerdeError> {
+        Ok(())
+    }
+}
+
+/// Writes the contents of an enum variant, i.e the `[...]` or `{...}` in `{"Variant":[...]}`.
+pub struct VariantWriter<'a, S: JsonBuffer> {
+    obj: ObjectWriter<'a, S>,
+    first_element: bool,
+    close: char,
+}
+
+impl<'a, S: JsonBuffer> VariantWriter<'a, S> {
+    fn start(
+        mut obj: ObjectWriter<'a, S>,
+        variant: &'static str,
+        open: char,
+        close: char,
+    ) -> VariantWriter<'a, S> {
+        obj.key(variant);
+        obj.buf.push(open);
+        VariantWriter {
+            obj,
+            first_element: true,
+            close,
+        }
+    }
+
+    fn comma(&mut self) {
+        match self.first_element {
+            true => self.first_element = false,
+            false => self.obj.buf.push(','),
+        }
+    }
+}
+
+impl<S: JsonBuffer> Drop for VariantWriter<'_, S> {
+    fn drop(&mut self) {
+        // runs before the object itself is closed
+        self.obj.buf.push(self.close);
+    }
+}
+
+impl<S: JsonBuffer> SerializeTupleVariant for VariantWriter<'_, S> {
+    type Ok = ();
+    type Error = SerdeError;
+
+    #[inline]
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SerdeError> {
+        self.comma();
+        value.serialize(SingleValueSerializer::new(self.obj.buf))
+    }
+
+    #[inline(always)]
+    fn end(self) -> Result<(), SerdeError> {
+        Ok(())
+    }
+}
+
+impl<S: JsonBuffer> SerializeStructVariant for VariantWriter<'_, S> {
+    type Ok = ();
+    type Error = SerdeError;
+
+    #[inline]
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), SerdeError> {
+        self.comma();
+        KeySerializer::new(self.obj.buf).write_str(key);
+        value.serialize(SingleValueSerializer::new(self.obj.buf))
+    }
+
+    #[inline(always)]
+    fn end(self) -> Result<(), SerdeError> {
+        Ok(())
+    }
+}
+
+/// Adds the `+` serde_json writes before positive exponents, so `1e300` becomes `1e+300`.
+fn signed_exponent<'b>(formatted: &'b str, buf: &'b mut [u8; 32]) -> &'b str {
+    match formatted.split_once('e') {
+        Some((mantissa, exponent)) if !exponent.starts_with('-') => {
+            let len = mantissa.len() + 2 + exponent.len();
+            buf[..mantissa.len()].copy_from_slice(mantissa.as_bytes());
+            buf[mantissa.len()..mantissa.len() + 2].copy_from_slice(b"e+");
+            buf[mantissa.len() + 2..len].copy_from_slice(exponent.as_bytes());
+            // only ascii was copied in
+            unsafe { core::str::from_utf8_unchecked(&buf[..len]) }
+        }
+        _ => formatted,
+    }
+}
+
+impl<S: JsonBuffer> SingleValueSerializer<'_, S> {
+    /// Writes a float the way serde_json does: `null` if it isn't finite, with a `+` on positive exponents.
+    fn write_float(self, finite: bool, v: impl ryu::Float) {
+        match finite {
+            true => {
+                let mut float_buf = ryu::Buffer::new();
+                let mut signed_buf = [0; 32];
+                let formatted = signed_exponent(float_buf.format_finite(v), &mut signed_buf);
+                self.write(RawNumber::create(formatted))
+            }
+            false => self.write(crate::Null),
+        }
+    }
+}
+
+macro_rules! serialize_int_key {
+    ($($method:ident: $ty:ty),*) => {
+        $(
+            #[inline]
+            fn $method(self, v: $ty) -> Result<(), SerdeError> {
+                let mut int_buf = itoa::Buffer::new();
+                self.write_unescaped(int_buf.format(v));
+                Ok(())
+            }
+        )*
+    }
+}
+
+macro_rules! serialize_float_key {
+    ($($method:ident: $ty:ty),*) => {
+        $(
+            #[inline]
+            fn $method(self, v: $ty) -> Result<(), SerdeError> {
+                match v.is_finite() {
+                    true => {
+                        let mut float_buf = ryu::Buffer::new();
+                        let mut signed_buf = [0; 32];
+                        self.write_unescaped(signed_exponent(float_buf.format_finite(v), &mut signed_buf));
+                        Ok(())
+                    }
+                    false => Err(SerdeError(String::from("float key must be finite"))),
+                }
+            }
+        )*
+    }
+}
+
+/// Map keys are written as strings; numbers, bools and unit variants are quoted, and anything else is an error.
+impl<'a, S: JsonBuffer> ser::Serializer for KeySerializer<'a, S> {
+    type Ok = ();
+    type Error = SerdeError;
+
+    type SerializeSeq = Impossible<(), SerdeError>;
+    type SerializeTuple = Impossible<(), SerdeError>;
+    type SerializeTupleStruct = Impossible<(), SerdeError>;
+    type SerializeTupleVariant = Impossible<(), SerdeError>;
+    type SerializeMap = Impossible<(), SerdeError>;
+    type SerializeStruct = Impossible<(), SerdeError>;
+    type SerializeStructVariant = Impossible<(), SerdeError>;
+
+    serialize_int_key!(
+        serialize_i8: i8,
+        serialize_i16: i16,
+        serialize_i32: i32,
+        serialize_i64: i64,
+        serialize_i128: i128,
+        serialize_u8: u8,
+        serialize_u16: u16,
+        serialize_u32: u32,
+        serialize_u64: u64,
+        serialize_u128: u128
+    );
+
+    serialize_float_key!(serialize_f32: f32, serialize_f64: f64);
+
+    fn serialize_bool(self, v: bool) -> Result<(), SerdeError> {
+        self.write_unescaped(if v { "true" } else { "false" });
+        Ok(())
+    }
+
+    fn serialize_char(self, v: char) -> Result<(), SerdeError> {
+        self.write_str(v.encode_utf8(&mut [0; 4]));
+        Ok(())
+    }
+
+    fn serialize_str(self, v: &str) -> Result<(), SerdeError> {
+        self.write_str(v);
+        Ok(())
+    }
+
+    fn serialize_bytes(self, _v: &[u8]) -> Result<(), SerdeError> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_none(self) -> Result<(), SerdeError> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, _value: &T) -> Result<(), SerdeError> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_unit(self) -> Result<(), SerdeError> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<(), SerdeError> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<(), SerdeError> {
+        self.write_str(variant);
+        Ok(())
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<(), SerdeError> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<(), SerdeError> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, SerdeError> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, SerdeError> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct, SerdeError> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, SerdeError> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, SerdeError> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, SerdeError> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, SerdeError> {
+        Err(key_must_be_a_string())
+    }
+
+    fn collect_str<T: fmt::Display + ?Sized>(self, value: &T) -> Result<(), SerdeError> {
+        self.write_display(value);
+        Ok(())
+    }
+}
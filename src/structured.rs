@@ -0,0 +1,716 @@
+//! Writing structured values from [valuable](https://docs.rs/valuable) and [sval](https://docs.rs/sval), each behind a cargo feature of the same name.
+
+#[cfg(feature = "valuable")]
+use valuable::{Fields, NamedValues, Slice, Value, Visit};
+
+#[cfg(feature = "sval")]
+use crate::escape::escape_str;
+#[cfg(feature = "valuable")]
+use crate::{ArrayWriter, Key, KeySerializer, ObjectWriter};
+use crate::{JsonBuffer, WriteToJson, WriteToJsonRef};
+
+/// Any [Valuable](valuable::Valuable) value, written by visiting it.
+///
+/// Structs and maps are written as objects, lists and tuples as arrays (except for `()`, which is `null`), and enums the way serde_json writes them: unit variants as strings, and anything else as `{"Variant": fields}`.
+/// Single unnamed fields are written as the value itself.
+/// # Examples
+/// ```
+/// use nyoom_json::{Serializer, Valuable};
+/// use valuable::{Fields, NamedField, NamedValues, StructDef, Structable, Value, Visit};
+///
+/// struct Cat {
+///     name: &'static str,
+///     toys: Vec<&'static str>,
+/// }
+///
+/// static CAT_FIELDS: &[NamedField<'static>] = &[NamedField::new("name"), NamedField::new("toys")];
+///
+/// impl valuable::Valuable for Cat {
+///     fn as_value(&self) -> Value<'_> {
+///         Value::Structable(self)
+///     }
+///
+///     fn visit(&self, visit: &mut dyn Visit) {
+///         visit.visit_named_fields(&NamedValues::new(
+///             CAT_FIELDS,
+///             &[self.name.as_value(), self.toys.as_value()],
+///         ));
+///     }
+/// }
+///
+/// impl Structable for Cat {
+///     fn definition(&self) -> StructDef<'_> {
+///         StructDef::new_static("Cat", Fields::Named(CAT_FIELDS))
+///     }
+/// }
+///
+/// let cat = Cat { name: "mittens", toys: vec!["mouse", "feather"] };
+///
+/// let mut out = String::new();
+/// let mut ser = Serializer::new(&mut out);
+/// ser.write(Valuable(&cat));
+///
+/// assert_eq!(out, r#"{"name":"mittens","toys":["mouse","feather"]}"#);
+/// ```
+#[cfg(feature = "valuable")]
+#[derive(Clone, Copy, Debug)]
+pub struct Valuable<T>(pub T);
+
+#[cfg(feature = "valuable")]
+impl<S: JsonBuffer, T: valuable::Valuable> WriteToJsonRef<S> for Valuable<T> {
+    #[inline]
+    fn write_to_json_ref(&self, out: &mut S) {
+        write_valuable(self.0.as_value(), out)
+    }
+}
+
+#[cfg(feature = "valuable")]
+impl<S: JsonBuffer, T: valuable::Valuable> WriteToJson<S> for Valuable<T> {
+    #[inline(always)]
+    fn write_to_json(self, out: &mut S) {
+        self.write_to_json_ref(out)
+    }
+}
+
+#[cfg(feature = "valuable")]
+fn write_valuable<S: JsonBuffer>(value: Value<'_>, out: &mut S) {
+    match value {
+        Value::Bool(v) => v.write_to_json(out),
+        Value::Char(v) => v.write_to_json(out),
+        Value::F32(v) => v.write_to_json(out),
+        Value::F64(v) => v.write_to_json(out),
+        Value::I8(v) => v.write_to_json(out),
+        Value::I16(v) => v.write_to_json(out),
+        Value::I32(v) => v.write_to_json(out),
+        Value::I64(v) => v.write_to_json(out),
+        Value::I128(v) => v.write_to_json(out),
+        Value::Isize(v) => v.write_to_json(out),
+        Value::U8(v) => v.write_to_json(out),
+        Value::U16(v) => v.write_to_json(out),
+        Value::U32(v) => v.write_to_json(out),
+        Value::U64(v) => v.write_to_json(out),
+        Value::U128(v) => v.write_to_json(out),
+        Value::Usize(v) => v.write_to_json(out),
+        Value::String(v) => v.write_to_json(out),
+        #[cfg(feature = "std")]
+        Value::Path(v) => crate::DisplayStr(v.display()).write_to_json(out),
+        #[cfg(feature = "std")]
+        Value::Error(v) => crate::DisplayStr(v).write_to_json(out),
+        Value::Listable(v) => v.visit(&mut ArrayVisitor(&mut ArrayWriter::start(out))),
+        Value::Tuplable(v) if v.definition().is_unit() => out.push_str("null"),
+        Value::Tuplable(v) => v.visit(&mut ArrayVisitor(&mut ArrayWriter::start(out))),
+        Value::Mappable(v) => v.visit(&mut ObjectVisitor(&mut ObjectWriter::start(out))),
+        Value::Structable(v) => match v.definition().fields() {
+            Fields::Unnamed(0) => out.push_str("null"),
+            fields => write_fields(fields, v, out),
+        },
+        Value::Enumerable(v) => {
+            let variant = v.variant();
+            match variant.fields() {
+                Fields::Unnamed(0) => variant.name().write_to_json_ref(out),
+                fields => {
+                    let mut obj = ObjectWriter::start(out);
+                    obj.key(variant.name());
+                    write_fields(fields, v, obj.buf);
+                }
+            }
+        }
+        // `Value::Unit`, along with anything added to valuable later on
+        _ => out.push_str("null"),
+    }
+}
+
+/// Writes the fields of a struct or enum variant.
+#[cfg(feature = "valuable")]
+fn write_fields<S: JsonBuffer, V: valuable::Valuable + ?Sized>(
+    fields: &Fields<'_>,
+    value: &V,
+    out: &mut S,
+) {
+    match fields {
+        Fields::Named(_) => value.visit(&mut ObjectVisitor(&mut ObjectWriter::start(out))),
+        Fields::Unnamed(1) => {
+            let mut single = SingleVisitor(Some(out));
+            value.visit(&mut single);
+            if let Some(out) = single.0 {
+                out.push_str("null");
+            }
+        }
+        Fields::Unnamed(_) => value.visit(&mut ArrayVisitor(&mut ArrayWriter::start(out))),
+    }
+}
+
+/// Adds everything it visits to an array.
+#[cfg(feature = "valuable")]
+struct ArrayVisitor<'w, 'a, S: JsonBuffer>(&'w mut ArrayWriter<'a, S>);
+
+#[cfg(feature = "valuable")]
+impl<S: JsonBuffer> Visit for ArrayVisitor<'_, '_, S> {
+    fn visit_value(&mut self, value: Value<'_>) {
+        self.0.comma();
+        write_valuable(value, self.0.buf);
+    }
+
+    fn visit_unnamed_fields(&mut self, values: &[Value<'_>]) {
+        for value in values {
+            self.visit_value(*value);
+        }
+    }
+
+    fn visit_primitive_slice(&mut self, slice: Slice<'_>) {
+        for value in slice {
+            self.visit_value(value);
+        }
+    }
+}
+
+/// Adds every field or entry it visits to an object.
+#[cfg(feature = "valuable")]
+struct ObjectVisitor<'w, 'a, S: JsonBuffer>(&'w mut ObjectWriter<'a, S>);
+
+#[cfg(feature = "valuable")]
+impl<S: JsonBuffer> Visit for ObjectVisitor<'_, '_, S> {
+    fn visit_value(&mut self, _value: Value<'_>) {}
+
+    fn visit_named_fields(&mut self, named_values: &NamedValues<'_>) {
+        for (field, value) in named_values {
+            self.0.key(field.name());
+            write_valuable(*value, self.0.buf);
+        }
+    }
+
+    fn visit_entry(&mut self, key: Value<'_>, value: Value<'_>) {
+        self.0.key(ValuableKey(key));
+        write_valuable(value, self.0.buf);
+    }
+}
+
+/// Writes the first unnamed field it visits.
+#[cfg(feature = "valuable")]
+struct SingleVisitor<'a, S: JsonBuffer>(Option<&'a mut S>);
+
+#[cfg(feature = "valuable")]
+impl<S: JsonBuffer> Visit for SingleVisitor<'_, S> {
+    fn visit_value(&mut self, _value: Value<'_>) {}
+
+    fn visit_unnamed_fields(&mut self, values: &[Value<'_>]) {
+        if let (Some(value), Some(out)) = (values.first(), self.0.take()) {
+            write_valuable(*value, out);
+        }
+    }
+}
+
+/// Map keys are written as strings; anything that isn't a string, number, bool or char uses its `Debug` output.
+#[cfg(feature = "valuable")]
+struct ValuableKey<'a>(Value<'a>);
+
+#[cfg(feature = "valuable")]
+impl Key for ValuableKey<'_> {
+    fn write_key<S: JsonBuffer>(&self, ser: KeySerializer<'_, S>) {
+        match self.0 {
+            Value::String(v) => v.write_key(ser),
+            Value::Char(v) => v.write_key(ser),
+            Value::Bool(v) => ser.write_unescaped(if v { "true" } else { "false" }),
+            Value::I8(v) => v.write_key(ser),
+            Value::I16(v) => v.write_key(ser),
+            Value::I32(v) => v.write_key(ser),
+            Value::I64(v) => v.write_key(ser),
+            Value::I128(v) => v.write_key(ser),
+            Value::Isize(v) => v.write_key(ser),
+            Value::U8(v) => v.write_key(ser),
+            Value::U16(v) => v.write_key(ser),
+            Value::U32(v) => v.write_key(ser),
+            Value::U64(v) => v.write_key(ser),
+            Value::U128(v) => v.write_key(ser),
+            Value::Usize(v) => v.write_key(ser),
+            ref v => ser.write_display(format_args!("{:?}", v)),
+        }
+    }
+}
+
+/// Any [sval::Value](sval::Value), written by streaming it.
+///
+/// The output matches sval_json: records and maps are written as objects, sequences and tuples as arrays, and enum variants as either their label or `{"Label": value}`.
+///
+/// Streaming fails if a map key is a map or sequence, if the value is nested more than 128 levels deep, or if the value's own `stream` fails. Output always stays valid JSON:
+/// whatever was being written when the stream failed is replaced by `null`, and anything left open is closed, so the rest of the value is cut off.
+/// # Examples
+/// ```
+/// use nyoom_json::{Serializer, Sval};
+///
+/// struct Cat {
+///     name: &'static str,
+///     lives: u8,
+/// }
+///
+/// impl sval::Value for Cat {
+///     fn stream<'sval, S: sval::Stream<'sval> + ?Sized>(&'sval self, stream: &mut S) -> sval::Result {
+///         stream.record_begin(None, Some(&sval::Label::new("Cat")), None, Some(2))?;
+///
+///         stream.record_value_begin(None, &sval::Label::new("name"))?;
+///         stream.value(self.name)?;
+///         stream.record_value_end(None, &sval::Label::new("name"))?;
+///
+///         stream.record_value_begin(None, &sval::Label::new("lives"))?;
+///         stream.value(&self.lives)?;
+///         stream.record_value_end(None, &sval::Label::new("lives"))?;
+///
+///         stream.record_end(None, Some(&sval::Label::new("Cat")), None)
+///     }
+/// }
+///
+/// let mut out = String::new();
+/// let mut ser = Serializer::new(&mut out);
+///
+/// let mut arr = ser.array();
+/// arr.add(Sval(Cat { name: "mittens", lives: 9 }));
+/// arr.add(Sval([Some(1.5), None]));
+/// arr.end();
+///
+/// assert_eq!(out, r#"[{"name":"mittens","lives":9},[1.5,null]]"#);
+/// ```
+#[cfg(feature = "sval")]
+#[derive(Clone, Copy, Debug)]
+pub struct Sval<T>(pub T);
+
+#[cfg(feature = "sval")]
+impl<S: JsonBuffer, T: sval::Value> WriteToJsonRef<S> for Sval<T> {
+    #[inline]
+    fn write_to_json_ref(&self, out: &mut S) {
+        let mut stream = JsonStream::new(out);
+        if self.0.stream(&mut stream).is_err() {
+            stream.recover();
+        }
+    }
+}
+
+#[cfg(feature = "sval")]
+impl<S: JsonBuffer, T: sval::Value> WriteToJson<S> for Sval<T> {
+    #[inline(always)]
+    fn write_to_json(self, out: &mut S) {
+        self.write_to_json_ref(out)
+    }
+}
+
+/// A [sval::Stream](sval::Stream) that writes JSON into a buffer, following the same rules as sval_json.
+#[cfg(feature = "sval")]
+struct JsonStream<'a, S: JsonBuffer> {
+    out: &'a mut S,
+    /// Whether an enum has begun, but none of its variant has been written yet.
+    is_internally_tagged: bool,
+    is_current_depth_empty: bool,
+    /// Whether text needs its own quotes, i.e it isn't part of a map key.
+    is_text_quoted: bool,
+    /// Whether a quoted string has been started and not yet closed.
+    is_in_text: bool,
+    /// Whether a value is expected next, i.e one would be missing if the stream stopped here.
+    is_value_pending: bool,
+    /// Which of the open containers are objects, one bit per level of nesting.
+    open_objects: u128,
+    depth: u32,
+}
+
+#[cfg(feature = "sval")]
+impl<'a, S: JsonBuffer> JsonStream<'a, S> {
+    fn new(out: &'a mut S) -> JsonStream<'a, S> {
+        JsonStream {
+            out,
+            is_internally_tagged: false,
+            is_current_depth_empty: true,
+            is_text_quoted: true,
+            is_in_text: false,
+            is_value_pending: true,
+            open_objects: 0,
+            depth: 0,
+        }
+    }
+
+    /// Brings the output back to valid JSON after the stream has failed, by finishing whatever was being written and closing everything that's still open.
+    fn recover(&mut self) {
+        if self.is_in_text {
+            self.out.push('"');
+        }
+
+        if !self.is_text_quoted {
+            self.key_end();
+        }
+
+        if self.is_value_pending {
+            self.out.push_str("null");
+        }
+
+        while self.depth > 0 {
+            self.depth -= 1;
+            match self.open_objects & (1 << self.depth) != 0 {
+                true => self.out.push('}'),
+                false => self.out.push(']'),
+            }
+        }
+    }
+
+    fn value_begin(&mut self) {
+        self.is_current_depth_empty = false;
+        if self.is_text_quoted {
+            self.is_value_pending = false;
+        }
+    }
+
+    fn int<T: WriteToJson<S> + itoa::Integer>(&mut self, v: T) -> sval::Result {
+        self.value_begin();
+        match self.is_text_quoted {
+            true => v.write_to_json(self.out),
+            // map keys are already quoted
+            false => self.out.push_str(itoa::Buffer::new().format(v)),
+        }
+        Ok(())
+    }
+
+    fn float<T: WriteToJson<S> + ryu::Float>(&mut self, v: T) -> sval::Result {
+        self.value_begin();
+        match self.is_text_quoted {
+            true => v.write_to_json(self.out),
+            false => self.out.push_str(ryu::Buffer::new().format(v)),
+        }
+        Ok(())
+    }
+
+    fn text(&mut self, v: &str) -> sval::Result {
+        sval::Stream::text_begin(self, Some(v.len()))?;
+        escape_str(v, self.out);
+        sval::Stream::text_end(self)
+    }
+
+    fn container_begin(&mut self, open: char) -> sval::Result {
+        // maps and sequences can't be written into a key
+        if !self.is_text_quoted || self.depth == u128::BITS {
+            return Err(sval::Error::new());
+        }
+
+        self.value_begin();
+        match open {
+            '{' => self.open_objects |= 1 << self.depth,
+            _ => self.open_objects &= !(1 << self.depth),
+        }
+        self.depth += 1;
+
+        self.is_current_depth_empty = true;
+        self.out.push(open);
+        Ok(())
+    }
+
+    fn container_end(&mut self, close: char) -> sval::Result {
+        self.depth = self.depth.saturating_sub(1);
+        self.is_current_depth_empty = false;
+        self.out.push(close);
+        Ok(())
+    }
+
+    fn key_begin(&mut self) {
+        self.is_text_quoted = false;
+        self.is_internally_tagged = false;
+        match self.is_current_depth_empty {
+            true => self.out.push('"'),
+            false => self.out.push_str(",\""),
+        }
+    }
+
+    fn key_end(&mut self) {
+        self.out.push_str("\":");
+        self.is_text_quoted = true;
+        self.is_value_pending = true;
+    }
+
+    /// Wraps the variant of an enum that has just begun in `{"Label": ...}`.
+    fn internally_tagged_begin(
+        &mut self,
+        label: Option<&sval::Label>,
+        index: Option<&sval::Index>,
+    ) -> sval::Result {
+        if self.is_internally_tagged {
+            self.is_internally_tagged = false;
+
+            if let Some(label) = label {
+                self.container_begin('{')?;
+                self.key_begin();
+                escape_str(label.as_str(), self.out);
+                self.key_end();
+            } else if let Some(index) = index.and_then(|index| index.to_i64()) {
+                self.container_begin('{')?;
+                self.key_begin();
+                self.int(index)?;
+                self.key_end();
+            }
+        }
+
+        Ok(())
+    }
+
+    fn internally_tagged_end(&mut self, label: Option<&sval::Label>, index: Option<&sval::Index>) {
+        self.is_internally_tagged =
+            label.is_some() || index.and_then(|index| index.to_i64()).is_some();
+    }
+}
+
+#[cfg(feature = "sval")]
+impl<'sval, S: JsonBuffer> sval::Stream<'sval> for JsonStream<'_, S> {
+    fn null(&mut self) -> sval::Result {
+        self.value_begin();
+        self.out.push_str("null");
+        Ok(())
+    }
+
+    fn bool(&mut self, v: bool) -> sval::Result {
+        self.value_begin();
+        self.out.push_str(if v { "true" } else { "false" });
+        Ok(())
+    }
+
+    fn text_begin(&mut self, _num_bytes: Option<usize>) -> sval::Result {
+        self.value_begin();
+        if self.is_text_quoted {
+            self.is_in_text = true;
+            self.out.push('"');
+        }
+        Ok(())
+    }
+
+    fn text_fragment_computed(&mut self, fragment: &str) -> sval::Result {
+        escape_str(fragment, self.out);
+        Ok(())
+    }
+
+    fn text_end(&mut self) -> sval::Result {
+        if self.is_text_quoted {
+            self.is_in_text = false;
+            self.out.push('"');
+        }
+        Ok(())
+    }
+
+    fn u8(&mut self, v: u8) -> sval::Result {
+        self.int(v)
+    }
+
+    fn u16(&mut self, v: u16) -> sval::Result {
+        self.int(v)
+    }
+
+    fn u32(&mut self, v: u32) -> sval::Result {
+        self.int(v)
+    }
+
+    fn u64(&mut self, v: u64) -> sval::Result {
+        self.int(v)
+    }
+
+    fn u128(&mut self, v: u128) -> sval::Result {
+        self.int(v)
+    }
+
+    fn i8(&mut self, v: i8) -> sval::Result {
+        self.int(v)
+    }
+
+    fn i16(&mut self, v: i16) -> sval::Result {
+        self.int(v)
+    }
+
+    fn i32(&mut self, v: i32) -> sval::Result {
+        self.int(v)
+    }
+
+    fn i64(&mut self, v: i64) -> sval::Result {
+        self.int(v)
+    }
+
+    fn i128(&mut self, v: i128) -> sval::Result {
+        self.int(v)
+    }
+
+    fn f32(&mut self, v: f32) -> sval::Result {
+        self.float(v)
+    }
+
+    fn f64(&mut self, v: f64) -> sval::Result {
+        self.float(v)
+    }
+
+    fn map_begin(&mut self, _num_entries: Option<usize>) -> sval::Result {
+        self.container_begin('{')
+    }
+
+    fn map_key_begin(&mut self) -> sval::Result {
+        self.key_begin();
+        Ok(())
+    }
+
+    fn map_key_end(&mut self) -> sval::Result {
+        self.key_end();
+        Ok(())
+    }
+
+    fn map_value_begin(&mut self) -> sval::Result {
+        Ok(())
+    }
+
+    fn map_value_end(&mut self) -> sval::Result {
+        Ok(())
+    }
+
+    fn map_end(&mut self) -> sval::Result {
+        self.container_end('}')
+    }
+
+    fn seq_begin(&mut self, _num_entries: Option<usize>) -> sval::Result {
+        self.container_begin('[')
+    }
+
+    fn seq_value_begin(&mut self) -> sval::Result {
+        self.is_internally_tagged = false;
+        if !self.is_current_depth_empty {
+            self.out.push(',');
+        }
+        self.is_value_pending = true;
+        Ok(())
+    }
+
+    fn seq_value_end(&mut self) -> sval::Result {
+        Ok(())
+    }
+
+    fn seq_end(&mut self) -> sval::Result {
+        self.container_end(']')
+    }
+
+    fn enum_begin(
+        &mut self,
+        _tag: Option<&sval::Tag>,
+        label: Option<&sval::Label>,
+        index: Option<&sval::Index>,
+    ) -> sval::Result {
+        self.internally_tagged_begin(label, index)?;
+        self.is_internally_tagged = true;
+        self.is_current_depth_empty = true;
+        Ok(())
+    }
+
+    fn enum_end(
+        &mut self,
+        tag: Option<&sval::Tag>,
+        label: Option<&sval::Label>,
+        index: Option<&sval::Index>,
+    ) -> sval::Result {
+        if self.is_current_depth_empty {
+            self.tag(tag, label, index)?;
+        }
+
+        match self.is_internally_tagged {
+            true => self.container_end('}'),
+            false => {
+                self.internally_tagged_end(label, index);
+                Ok(())
+            }
+        }
+    }
+
+    fn tagged_begin(
+        &mut self,
+        _tag: Option<&sval::Tag>,
+        label: Option<&sval::Label>,
+        index: Option<&sval::Index>,
+    ) -> sval::Result {
+        self.internally_tagged_begin(label, index)?;
+        self.is_current_depth_empty = true;
+        Ok(())
+    }
+
+    fn tagged_end(
+        &mut self,
+        tag: Option<&sval::Tag>,
+        label: Option<&sval::Label>,
+        index: Option<&sval::Index>,
+    ) -> sval::Result {
+        if self.is_current_depth_empty {
+            self.tag(tag, label, index)?;
+        }
+
+        self.internally_tagged_end(label, index);
+        Ok(())
+    }
+
+    fn tag(
+        &mut self,
+        tag: Option<&sval::Tag>,
+        label: Option<&sval::Label>,
+        index: Option<&sval::Index>,
+    ) -> sval::Result {
+        self.is_internally_tagged = false;
+        self.is_current_depth_empty = false;
+
+        match (tag, label, index.and_then(|index| index.to_i64())) {
+            (Some(&sval::tags::RUST_OPTION_NONE), _, _) => self.null(),
+            (_, Some(label), _) => self.text(label.as_str()),
+            (_, None, Some(index)) => self.int(index),
+            _ => self.null(),
+        }
+    }
+
+    fn record_begin(
+        &mut self,
+        _tag: Option<&sval::Tag>,
+        label: Option<&sval::Label>,
+        index: Option<&sval::Index>,
+        num_entries: Option<usize>,
+    ) -> sval::Result {
+        self.internally_tagged_begin(label, index)?;
+        self.map_begin(num_entries)
+    }
+
+    fn record_value_begin(
+        &mut self,
+        _tag: Option<&sval::Tag>,
+        label: &sval::Label,
+    ) -> sval::Result {
+        self.key_begin();
+        escape_str(label.as_str(), self.out);
+        self.key_end();
+        Ok(())
+    }
+
+    fn record_value_end(&mut self, _tag: Option<&sval::Tag>, _label: &sval::Label) -> sval::Result {
+        Ok(())
+    }
+
+    fn record_end(
+        &mut self,
+        _tag: Option<&sval::Tag>,
+        label: Option<&sval::Label>,
+        index: Option<&sval::Index>,
+    ) -> sval::Result {
+        self.map_end()?;
+        self.internally_tagged_end(label, index);
+        Ok(())
+    }
+
+    fn tuple_begin(
+        &mut self,
+        _tag: Option<&sval::Tag>,
+        label: Option<&sval::Label>,
+        index: Option<&sval::Index>,
+        num_entries: Option<usize>,
+    ) -> sval::Result {
+        self.internally_tagged_begin(label, index)?;
+        self.seq_begin(num_entries)
+    }
+
+    fn tuple_end(
+        &mut self,
+        _tag: Option<&sval::Tag>,
+        label: Option<&sval::Label>,
+        index: Option<&sval::Index>,
+    ) -> sval::Result {
+        self.seq_end()?;
+        self.internally_tagged_end(label, index);
+        Ok(())
+    }
+}
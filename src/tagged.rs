@@ -0,0 +1,144 @@
+//! Helpers for writing enums by hand, following serde's tagging conventions.
+//!
+//! # Untagged variants
+//! An untagged variant is just its content, so there's no helper for it: write the content directly.
+//! ```
+//! use nyoom_json::{JsonBuffer, Serializer, SingleValueSerializer, WriteToJsonRef};
+//!
+//! enum CatId {
+//!     Number(u64),
+//!     Name(&'static str),
+//!     Chipped { chip: u64 },
+//! }
+//!
+//! impl<S: JsonBuffer> WriteToJsonRef<S> for CatId {
+//!     fn write_to_json_ref(&self, out: &mut S) {
+//!         let ser = SingleValueSerializer::new(out);
+//!         match self {
+//!             CatId::Number(n) => ser.write(*n),
+//!             CatId::Name(name) => ser.write(*name),
+//!             CatId::Chipped { chip } => {
+//!                 let mut obj = ser.object();
+//!                 obj.field("chip", *chip);
+//!             }
+//!         }
+//!     }
+//! }
+//!
+//! let mut out = String::new();
+//! let mut ser = Serializer::new(&mut out);
+//! ser.write(&[CatId::Number(9), CatId::Name("mittens"), CatId::Chipped { chip: 42 }]);
+//!
+//! assert_eq!(out, r#"[9,"mittens",{"chip":42}]"#);
+//! ```
+
+use crate::{JsonBuffer, Key, ObjectWriter, SingleValueSerializer, WriteToJson};
+
+impl<'a, S: JsonBuffer> SingleValueSerializer<'a, S> {
+    /// Writes an externally tagged variant, `{"Variant":content}`: an object with the variant's name as its only key.
+    ///
+    /// Untagged variants don't need a helper; their content is written directly, as with [write](SingleValueSerializer::write).
+    ///
+    /// # Examples
+    /// ```
+    /// use nyoom_json::{JsonBuffer, Serializer, SingleValueSerializer, WriteToJsonRef};
+    ///
+    /// enum Toy {
+    ///     Mouse,
+    ///     Ball { bounciness: f32 },
+    /// }
+    ///
+    /// impl<S: JsonBuffer> WriteToJsonRef<S> for Toy {
+    ///     fn write_to_json_ref(&self, out: &mut S) {
+    ///         let ser = SingleValueSerializer::new(out);
+    ///         match self {
+    ///             Toy::Mouse => ser.write("Mouse"),
+    ///             Toy::Ball { bounciness } => ser.externally_tagged("Ball", |ser| {
+    ///                 let mut obj = ser.object();
+    ///                 obj.field("bounciness", *bounciness);
+    ///             }),
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// let mut out = String::new();
+    /// let mut ser = Serializer::new(&mut out);
+    /// ser.write(&[Toy::Mouse, Toy::Ball { bounciness: 0.5 }]);
+    ///
+    /// assert_eq!(out, r#"["Mouse",{"Ball":{"bounciness":0.5}}]"#);
+    /// ```
+    pub fn externally_tagged<K, F, O>(self, variant: K, encode: F) -> O
+    where
+        K: Key,
+        F: FnOnce(SingleValueSerializer<&mut S>) -> O,
+    {
+        self.object().complex_field(variant, encode)
+    }
+
+    /// Starts an internally tagged variant, `{"tag":"Variant",...}`: an object with the variant's name under `tag`, followed by whatever fields are added to the returned writer.
+    ///
+    /// # Examples
+    /// ```
+    /// use nyoom_json::{key, Serializer, SingleValueSerializer};
+    /// use std::collections::BTreeMap;
+    ///
+    /// let mut out = String::new();
+    /// let mut obj = SingleValueSerializer::new(&mut out).internally_tagged(key!("type"), "Ball");
+    /// obj.field("bounciness", 0.5);
+    /// obj.end();
+    ///
+    /// assert_eq!(out, r#"{"type":"Ball","bounciness":0.5}"#);
+    ///
+    /// // the rest of the object can also be flattened in from elsewhere
+    /// let toys = BTreeMap::from([("mouse", 2), ("ball", 1)]);
+    ///
+    /// let mut out = String::new();
+    /// let mut obj = SingleValueSerializer::new(&mut out).internally_tagged(key!("type"), "Inventory");
+    /// obj.flatten(&toys);
+    /// obj.end();
+    ///
+    /// assert_eq!(out, r#"{"type":"Inventory","ball":1,"mouse":2}"#);
+    /// ```
+    pub fn internally_tagged<K: Key>(
+        self,
+        tag: K,
+        variant: impl WriteToJson<S>,
+    ) -> ObjectWriter<'a, S> {
+        let mut obj = self.object();
+        obj.field(tag, variant);
+        obj
+    }
+
+    /// Writes an adjacently tagged variant, `{"tag":"Variant","content":content}`.
+    ///
+    /// Unit variants are usually written without any content; use [internally_tagged](SingleValueSerializer::internally_tagged) for those.
+    /// # Examples
+    /// ```
+    /// use nyoom_json::{key, Serializer, SingleValueSerializer};
+    ///
+    /// let mut out = String::new();
+    /// SingleValueSerializer::new(&mut out).adjacently_tagged(key!("t"), "Ball", key!("c"), |ser| {
+    ///     let mut arr = ser.array();
+    ///     arr.add(0.5);
+    ///     arr.add("red");
+    /// });
+    ///
+    /// assert_eq!(out, r#"{"t":"Ball","c":[0.5,"red"]}"#);
+    /// ```
+    pub fn adjacently_tagged<T, C, F, O>(
+        self,
+        tag: T,
+        variant: impl WriteToJson<S>,
+        content: C,
+        encode: F,
+    ) -> O
+    where
+        T: Key,
+        C: Key,
+        F: FnOnce(SingleValueSerializer<&mut S>) -> O,
+    {
+        let mut obj = self.object();
+        obj.field(tag, variant);
+        obj.complex_field(content, encode)
+    }
+}
@@ -0,0 +1,206 @@
+/// Splits a [template!](crate::template)'s minified text into the `S` static segments around its `H` holes.
+pub const fn segments<const H: usize, const S: usize>(
+    text: &'static str,
+    holes: [usize; H],
+) -> [&'static str; S] {
+    assert!(S == H + 1);
+
+    let mut out = [""; S];
+    let mut start = 0;
+    let mut i = 0;
+    while i < S {
+        let end = if i < H { holes[i] } else { text.len() };
+        let (segment, _) = text.as_bytes().split_at(end);
+        let (_, segment) = segment.split_at(start);
+        out[i] = match core::str::from_utf8(segment) {
+            Ok(segment) => segment,
+            Err(_) => panic!("template holes must sit between JSON tokens"),
+        };
+
+        start = end;
+        i += 1;
+    }
+
+    out
+}
+
+/// Defines a struct for a document whose shape never changes, and which is written by pushing precompiled chunks of text with only its fields written in between.
+///
+/// The document is written like JSON, with `(name: Type)` holes wherever a value goes; each hole becomes a field of the struct. Holes can't be used as keys.
+/// The shape is minified and checked at compile time (with every hole standing in for a value), and the struct implements [WriteToJson](crate::WriteToJson) and [WriteToJsonRef](crate::WriteToJsonRef).
+///
+/// Finding the holes takes a level of macro recursion for each hole, nested container and field with a `null`, `true` or `false` value, though runs of fields with literal values (or of holes) are taken several at a time.
+/// Wide templates that mix these can run into the compiler's recursion limit of 128; if one does, raise it with `#![recursion_limit = "256"]` at the top of the crate.
+/// # Examples
+/// ```
+/// use nyoom_json::{template, Serializer};
+///
+/// template! {
+///     /// The body of a cat lookup.
+///     pub struct CatResponse<'a> = {
+///         "status": "ok",
+///         "cat": {
+///             "name": (name: &'a str),
+///             "lives": (lives: u8),
+///             "toys": [(favourite_toy: Option<&'a str>), "box"]
+///         }
+///     }
+/// }
+///
+/// let mut out = String::new();
+/// let mut ser = Serializer::new(&mut out);
+/// ser.write(CatResponse {
+///     name: "mittens",
+///     lives: 9,
+///     favourite_toy: Some("mouse"),
+/// });
+///
+/// assert_eq!(
+///     out,
+///     r#"{"status":"ok","cat":{"name":"mittens","lives":9,"toys":["mouse","box"]}}"#
+/// );
+/// ```
+///
+/// ```compile_fail
+/// nyoom_json::template! {
+///     struct Broken = { "name": (name: &'static str) "lives": 9 }
+/// }
+/// ```
+#[macro_export]
+macro_rules! template {
+    // collects `(name: Type)` holes, in order, from anywhere in the document. Every step is a level of macro
+    // recursion, so whole fields and items are taken at once where possible, several at a time when they're alike.
+    (@holes $def:tt [$($holes:tt)*]
+        $k0:literal : ($f0:ident : $t0:ty), $k1:literal : ($f1:ident : $t1:ty),
+        $k2:literal : ($f2:ident : $t2:ty), $k3:literal : ($f3:ident : $t3:ty),
+        $($rest:tt)*
+    ) => {
+        $crate::template! { @holes $def [$($holes)* ($f0: $t0) ($f1: $t1) ($f2: $t2) ($f3: $t3)] $($rest)* }
+    };
+    (@holes $def:tt [$($holes:tt)*]
+        ($f0:ident : $t0:ty), ($f1:ident : $t1:ty), ($f2:ident : $t2:ty), ($f3:ident : $t3:ty),
+        $($rest:tt)*
+    ) => {
+        $crate::template! { @holes $def [$($holes)* ($f0: $t0) ($f1: $t1) ($f2: $t2) ($f3: $t3)] $($rest)* }
+    };
+    (@holes $def:tt $holes:tt
+        $k0:literal : $v0:literal, $k1:literal : $v1:literal, $k2:literal : $v2:literal, $k3:literal : $v3:literal,
+        $k4:literal : $v4:literal, $k5:literal : $v5:literal, $k6:literal : $v6:literal, $k7:literal : $v7:literal,
+        $($rest:tt)*
+    ) => {
+        $crate::template! { @holes $def $holes $($rest)* }
+    };
+    (@holes $def:tt $holes:tt
+        $v0:literal, $v1:literal, $v2:literal, $v3:literal, $v4:literal, $v5:literal, $v6:literal, $v7:literal,
+        $($rest:tt)*
+    ) => {
+        $crate::template! { @holes $def $holes $($rest)* }
+    };
+    (@holes $def:tt [$($holes:tt)*] $key:literal : ($field:ident : $ty:ty), $($rest:tt)*) => {
+        $crate::template! { @holes $def [$($holes)* ($field: $ty)] $($rest)* }
+    };
+    (@holes $def:tt [$($holes:tt)*] ($field:ident : $ty:ty), $($rest:tt)*) => {
+        $crate::template! { @holes $def [$($holes)* ($field: $ty)] $($rest)* }
+    };
+    (@holes $def:tt $holes:tt $key:literal : $value:literal, $($rest:tt)*) => {
+        $crate::template! { @holes $def $holes $($rest)* }
+    };
+    (@holes $def:tt $holes:tt $key:literal : $value:ident, $($rest:tt)*) => {
+        $crate::template! { @holes $def $holes $($rest)* }
+    };
+    (@holes $def:tt $holes:tt $key:literal : { $($inner:tt)* } $($rest:tt)*) => {
+        $crate::template! { @holes $def $holes $($inner)* $($rest)* }
+    };
+    (@holes $def:tt $holes:tt $key:literal : [ $($inner:tt)* ] $($rest:tt)*) => {
+        $crate::template! { @holes $def $holes $($inner)* $($rest)* }
+    };
+    (@holes $def:tt $holes:tt $value:literal, $($rest:tt)*) => {
+        $crate::template! { @holes $def $holes $($rest)* }
+    };
+    (@holes $def:tt $holes:tt $value:ident, $($rest:tt)*) => {
+        $crate::template! { @holes $def $holes $($rest)* }
+    };
+    (@holes $def:tt [$($holes:tt)*] ($field:ident : $ty:ty) $($rest:tt)*) => {
+        $crate::template! { @holes $def [$($holes)* ($field: $ty)] $($rest)* }
+    };
+    (@holes $def:tt [$($holes:tt)*] { $($inner:tt)* } $($rest:tt)*) => {
+        $crate::template! { @holes $def [$($holes)*] $($inner)* $($rest)* }
+    };
+    (@holes $def:tt [$($holes:tt)*] [ $($inner:tt)* ] $($rest:tt)*) => {
+        $crate::template! { @holes $def [$($holes)*] $($inner)* $($rest)* }
+    };
+    (@holes $def:tt [$($holes:tt)*] $other:tt $($rest:tt)*) => {
+        $crate::template! { @holes $def [$($holes)*] $($rest)* }
+    };
+
+    (@holes {
+        ($(#[$meta:meta])*) ($vis:vis) ($name:ident) ($($lt:lifetime),*) ($($body:tt)+)
+    } [$(($field:ident: $ty:ty))*]) => {
+        $(#[$meta])*
+        $vis struct $name<$($lt),*> {
+            $($vis $field: $ty,)*
+        }
+
+        const _: () = {
+            const SOURCE: &[u8] = stringify!($($body)+).as_bytes();
+            const HOLES: usize = <[&str]>::len(&[$(stringify!($field)),*]);
+
+            const CHECKED: () = {
+                const LEN: usize = $crate::__private::minify::<0, 0>(SOURCE, $crate::__private::Holes::Null).len;
+                let with_nulls = $crate::__private::minify::<LEN, HOLES>(SOURCE, $crate::__private::Holes::Null);
+                assert!(
+                    with_nulls.hole_count == HOLES,
+                    "template holes must be written as (name: Type)"
+                );
+                $crate::__private::minified_str(&with_nulls.text);
+            };
+
+            const LEN: usize = $crate::__private::minify::<0, 0>(SOURCE, $crate::__private::Holes::Remove).len;
+            const MINIFIED: $crate::__private::Minified<LEN, HOLES> =
+                $crate::__private::minify(SOURCE, $crate::__private::Holes::Remove);
+            const TEXT: &str = {
+                let _ = CHECKED;
+                match ::core::str::from_utf8(&MINIFIED.text) {
+                    Ok(text) => text,
+                    Err(_) => panic!("template is not valid UTF-8"),
+                }
+            };
+            const SEGMENTS: [&str; HOLES + 1] = $crate::__private::segments(TEXT, MINIFIED.holes);
+
+            impl<$($lt,)* __S: $crate::JsonBuffer> $crate::WriteToJsonRef<__S> for $name<$($lt),*>
+            where
+                $($ty: $crate::WriteToJsonRef<__S>,)*
+            {
+                #[inline]
+                #[allow(unused_mut, unused_assignments)]
+                fn write_to_json_ref(&self, out: &mut __S) {
+                    let mut i = 0;
+                    $(
+                        out.push_str(SEGMENTS[i]);
+                        $crate::WriteToJsonRef::write_to_json_ref(&self.$field, out);
+                        i += 1;
+                    )*
+                    out.push_str(SEGMENTS[i]);
+                }
+            }
+
+            impl<$($lt,)* __S: $crate::JsonBuffer> $crate::WriteToJson<__S> for $name<$($lt),*>
+            where
+                $($ty: $crate::WriteToJsonRef<__S>,)*
+            {
+                #[inline(always)]
+                fn write_to_json(self, out: &mut __S) {
+                    $crate::WriteToJsonRef::write_to_json_ref(&self, out)
+                }
+            }
+        };
+    };
+
+    ($(#[$meta:meta])* $vis:vis struct $name:ident $(<$($lt:lifetime),+ $(,)?>)? = $($body:tt)+) => {
+        $crate::template! {
+            @holes {
+                ($(#[$meta])*) ($vis) ($name) ($($($lt),+)?) ($($body)+)
+            } [] $($body)+
+        }
+    };
+}
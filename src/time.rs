@@ -0,0 +1,308 @@
+// the timestamp helpers are only needed by `SystemTime` and the date-time integrations
+#![cfg_attr(
+    not(any(feature = "std", feature = "chrono", feature = "time")),
+    allow(dead_code)
+)]
+
+use core::time::Duration;
+#[cfg(feature = "std")]
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::{JsonBuffer, WriteToJson, WriteToJsonRef};
+
+/// How a [Duration](core::time::Duration) is written into JSON.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum DurationFormat {
+    /// A number of seconds, with fractional seconds, like `90.5`.
+    #[default]
+    Seconds,
+    /// A whole number of milliseconds, like `90500`.
+    Milliseconds,
+    /// An ISO 8601 duration string, like `"PT1M30.5S"`.
+    Iso8601,
+}
+
+/// How a point in time, like a `SystemTime`, is written into JSON.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum TimestampFormat {
+    /// An RFC 3339 timestamp in UTC, like `"2023-04-01T12:30:00.250Z"`.
+    #[default]
+    Rfc3339,
+    /// Seconds since the Unix epoch, with fractional seconds, like `1680352200.25`.
+    UnixSeconds,
+    /// Whole milliseconds since the Unix epoch, like `1680352200250`.
+    UnixMilliseconds,
+}
+
+/// A [Duration](core::time::Duration) written in a specific [DurationFormat](DurationFormat), regardless of the buffer's [Options](crate::Options).
+/// # Examples
+/// ```
+/// use nyoom_json::{DurationAs, DurationFormat, Serializer};
+/// use std::time::Duration;
+///
+/// let nap = Duration::from_millis(5_400_250);
+///
+/// let mut out = String::new();
+/// let mut ser = Serializer::new(&mut out);
+///
+/// let mut arr = ser.array();
+/// arr.add(nap);
+/// arr.add(DurationAs(nap, DurationFormat::Milliseconds));
+/// arr.add(DurationAs(nap, DurationFormat::Iso8601));
+/// arr.end();
+///
+/// assert_eq!(out, r#"[5400.25,5400250,"PT1H30M0.25S"]"#);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct DurationAs(pub Duration, pub DurationFormat);
+
+impl<S: JsonBuffer> WriteToJson<S> for DurationAs {
+    fn write_to_json(self, out: &mut S) {
+        let DurationAs(duration, format) = self;
+        match format {
+            DurationFormat::Seconds => duration.as_secs_f64().write_to_json(out),
+            DurationFormat::Milliseconds => duration.as_millis().write_to_json(out),
+            DurationFormat::Iso8601 => {
+                let secs = duration.as_secs();
+                let (hours, minutes, secs) = (secs / 3600, secs / 60 % 60, secs % 60);
+                let mut int_buf = itoa::Buffer::new();
+
+                out.push_str("\"PT");
+                if hours > 0 {
+                    out.push_str(int_buf.format(hours));
+                    out.push('H');
+                }
+                if minutes > 0 {
+                    out.push_str(int_buf.format(minutes));
+                    out.push('M');
+                }
+                if secs > 0 || duration.subsec_nanos() > 0 || duration.is_zero() {
+                    out.push_str(int_buf.format(secs));
+                    write_fraction(out, duration.subsec_nanos(), false);
+                    out.push('S');
+                }
+                out.push('"');
+            }
+        }
+    }
+}
+
+impl<S: JsonBuffer> WriteToJson<S> for Duration {
+    #[inline]
+    fn write_to_json(self, out: &mut S) {
+        let format = out.options().duration_format;
+        DurationAs(self, format).write_to_json(out)
+    }
+}
+
+impl<S: JsonBuffer> WriteToJsonRef<S> for DurationAs {
+    #[inline(always)]
+    fn write_to_json_ref(&self, out: &mut S) {
+        (*self).write_to_json(out)
+    }
+}
+
+impl<S: JsonBuffer> WriteToJsonRef<S> for Duration {
+    #[inline(always)]
+    fn write_to_json_ref(&self, out: &mut S) {
+        (*self).write_to_json(out)
+    }
+}
+
+/// A `SystemTime` written in a specific [TimestampFormat](TimestampFormat), regardless of the buffer's [Options](crate::Options).
+/// # Examples
+/// ```
+/// use nyoom_json::{Serializer, TimestampAs, TimestampFormat};
+/// use std::time::{Duration, UNIX_EPOCH};
+///
+/// let dinner = UNIX_EPOCH + Duration::from_millis(1_680_352_200_250);
+///
+/// let mut out = String::new();
+/// let mut ser = Serializer::new(&mut out);
+///
+/// let mut arr = ser.array();
+/// arr.add(dinner);
+/// arr.add(TimestampAs(dinner, TimestampFormat::UnixMilliseconds));
+/// arr.end();
+///
+/// assert_eq!(out, r#"["2023-04-01T12:30:00.250Z",1680352200250]"#);
+/// ```
+#[cfg(feature = "std")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct TimestampAs(pub SystemTime, pub TimestampFormat);
+
+#[cfg(feature = "std")]
+impl<S: JsonBuffer> WriteToJson<S> for TimestampAs {
+    fn write_to_json(self, out: &mut S) {
+        let TimestampAs(time, format) = self;
+
+        // whole seconds relative to the epoch (rounded down), plus nanoseconds after that
+        let (secs, nanos) = match time.duration_since(UNIX_EPOCH).map_err(|e| e.duration()) {
+            Ok(since) => (since.as_secs() as i64, since.subsec_nanos()),
+            Err(before) => match before.subsec_nanos() {
+                0 => (-(before.as_secs() as i64), 0),
+                nanos => (-(before.as_secs() as i64) - 1, 1_000_000_000 - nanos),
+            },
+        };
+
+        write_timestamp(out, secs, nanos, format);
+    }
+}
+
+#[cfg(feature = "std")]
+impl<S: JsonBuffer> WriteToJson<S> for SystemTime {
+    #[inline]
+    fn write_to_json(self, out: &mut S) {
+        let format = out.options().timestamp_format;
+        TimestampAs(self, format).write_to_json(out)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<S: JsonBuffer> WriteToJsonRef<S> for TimestampAs {
+    #[inline(always)]
+    fn write_to_json_ref(&self, out: &mut S) {
+        (*self).write_to_json(out)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<S: JsonBuffer> WriteToJsonRef<S> for SystemTime {
+    #[inline(always)]
+    fn write_to_json_ref(&self, out: &mut S) {
+        (*self).write_to_json(out)
+    }
+}
+
+/// Writes a point in time, given as whole seconds since the Unix epoch (rounded down) plus nanoseconds after that. RFC 3339 timestamps are written in UTC.
+pub(crate) fn write_timestamp<S: JsonBuffer>(
+    out: &mut S,
+    secs: i64,
+    nanos: u32,
+    format: TimestampFormat,
+) {
+    match format {
+        TimestampFormat::Rfc3339 => {
+            let secs_of_day = secs.rem_euclid(86_400) as u32;
+            let (year, month, day) = civil_from_days(secs.div_euclid(86_400));
+            write_rfc3339(
+                out,
+                DateTime {
+                    year,
+                    month,
+                    day,
+                    hour: secs_of_day / 3600,
+                    minute: secs_of_day / 60 % 60,
+                    second: secs_of_day % 60,
+                    nanos,
+                    offset_seconds: 0,
+                },
+            );
+        }
+        TimestampFormat::UnixSeconds => match nanos {
+            // keep the fraction exact for times before the epoch, rather than subtracting it from the next whole second
+            0 => (secs as f64).write_to_json(out),
+            _ if secs < 0 => {
+                (-((-(secs + 1)) as f64 + (1_000_000_000 - nanos) as f64 / 1e9)).write_to_json(out)
+            }
+            _ => (secs as f64 + nanos as f64 / 1e9).write_to_json(out),
+        },
+        TimestampFormat::UnixMilliseconds => {
+            (secs as i128 * 1000 + (nanos / 1_000_000) as i128).write_to_json(out)
+        }
+    }
+}
+
+/// The broken down parts of a date and time, in some UTC offset.
+pub(crate) struct DateTime {
+    pub(crate) year: i64,
+    pub(crate) month: u32,
+    pub(crate) day: u32,
+    pub(crate) hour: u32,
+    pub(crate) minute: u32,
+    pub(crate) second: u32,
+    pub(crate) nanos: u32,
+    pub(crate) offset_seconds: i32,
+}
+
+/// Writes a quoted RFC 3339 timestamp. Years outside of 0000-9999 can't be represented in RFC 3339, and are written with a sign or extra digits, as ISO 8601 does.
+pub(crate) fn write_rfc3339<S: JsonBuffer>(out: &mut S, dt: DateTime) {
+    out.push('"');
+    if dt.year < 0 {
+        out.push('-');
+    }
+    write_padded(out, dt.year.unsigned_abs(), 4);
+    out.push('-');
+    write_padded(out, dt.month as u64, 2);
+    out.push('-');
+    write_padded(out, dt.day as u64, 2);
+    out.push('T');
+    write_padded(out, dt.hour as u64, 2);
+    out.push(':');
+    write_padded(out, dt.minute as u64, 2);
+    out.push(':');
+    write_padded(out, dt.second as u64, 2);
+    write_fraction(out, dt.nanos, true);
+
+    match dt.offset_seconds {
+        0 => out.push('Z'),
+        offset => {
+            out.push(if offset < 0 { '-' } else { '+' });
+            let offset_minutes = offset.unsigned_abs() / 60;
+            write_padded(out, (offset_minutes / 60) as u64, 2);
+            out.push(':');
+            write_padded(out, (offset_minutes % 60) as u64, 2);
+        }
+    }
+    out.push('"');
+}
+
+/// Writes `n`, zero padded to at least `width` digits.
+fn write_padded<S: JsonBuffer>(out: &mut S, n: u64, width: usize) {
+    let mut int_buf = itoa::Buffer::new();
+    let digits = int_buf.format(n);
+    for _ in digits.len()..width {
+        out.push('0');
+    }
+    out.push_str(digits);
+}
+
+/// Writes fractional seconds, if there are any. With `grouped`, the fraction is written as milli, micro or nanoseconds; otherwise, trailing zeros are dropped altogether.
+fn write_fraction<S: JsonBuffer>(out: &mut S, nanos: u32, grouped: bool) {
+    if nanos == 0 {
+        return;
+    }
+
+    let mut digits = [b'0'; 9];
+    let mut n = nanos;
+    for digit in digits.iter_mut().rev() {
+        *digit = b'0' + (n % 10) as u8;
+        n /= 10;
+    }
+
+    let len = match grouped {
+        true if nanos % 1_000_000 == 0 => 3,
+        true if nanos % 1_000 == 0 => 6,
+        true => 9,
+        false => digits.iter().rposition(|&d| d != b'0').unwrap() + 1,
+    };
+
+    out.push('.');
+    out.push_str(unsafe { core::str::from_utf8_unchecked(&digits[..len]) });
+}
+
+/// Converts days since 1970-01-01 into a (year, month, day) in the proleptic Gregorian calendar.
+///
+/// See <https://howardhinnant.github.io/date_algorithms.html#civil_from_days>.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + (month <= 2) as i64;
+    (year, month, day)
+}
@@ -0,0 +1,311 @@
+/// How deeply arrays and objects may nest before a value is rejected.
+const MAX_DEPTH: usize = 128;
+
+/// Checks that `bytes` is exactly one JSON value, optionally surrounded by whitespace.
+pub(crate) const fn is_value(bytes: &[u8]) -> bool {
+    match value(bytes, 0, 0) {
+        Some(end) => whitespace(bytes, end) == bytes.len(),
+        None => false,
+    }
+}
+
+/// Checks that `bytes` is exactly one quoted JSON string.
+pub(crate) const fn is_string(bytes: &[u8]) -> bool {
+    match at(bytes, 0) {
+        Some(b'"') => matches!(string(bytes, 1), Some(end) if end == bytes.len()),
+        _ => false,
+    }
+}
+
+/// Checks that `bytes` is exactly one JSON number.
+pub(crate) const fn is_number(bytes: &[u8]) -> bool {
+    matches!(number(bytes, 0), Some(end) if end == bytes.len())
+}
+
+/// Parses a value (and any whitespace before it) starting at `i`, returning the index just after it.
+const fn value(bytes: &[u8], i: usize, depth: usize) -> Option<usize> {
+    let i = whitespace(bytes, i);
+    match at(bytes, i) {
+        Some(b'{') => object(bytes, i + 1, depth + 1),
+        Some(b'[') => array(bytes, i + 1, depth + 1),
+        Some(b'"') => string(bytes, i + 1),
+        Some(b't') => literal(bytes, i, b"true"),
+        Some(b'f') => literal(bytes, i, b"false"),
+        Some(b'n') => literal(bytes, i, b"null"),
+        Some(b'-' | b'0'..=b'9') => number(bytes, i),
+        _ => None,
+    }
+}
+
+const fn object(bytes: &[u8], i: usize, depth: usize) -> Option<usize> {
+    if depth > MAX_DEPTH {
+        return None;
+    }
+
+    let mut i = whitespace(bytes, i);
+    if let Some(b'}') = at(bytes, i) {
+        return Some(i + 1);
+    }
+
+    loop {
+        i = whitespace(bytes, i);
+        i = match at(bytes, i) {
+            Some(b'"') => match string(bytes, i + 1) {
+                Some(end) => whitespace(bytes, end),
+                None => return None,
+            },
+            _ => return None,
+        };
+
+        match at(bytes, i) {
+            Some(b':') => i += 1,
+            _ => return None,
+        }
+
+        i = match value(bytes, i, depth) {
+            Some(end) => whitespace(bytes, end),
+            None => return None,
+        };
+
+        match at(bytes, i) {
+            Some(b',') => i += 1,
+            Some(b'}') => return Some(i + 1),
+            _ => return None,
+        }
+    }
+}
+
+const fn array(bytes: &[u8], i: usize, depth: usize) -> Option<usize> {
+    if depth > MAX_DEPTH {
+        return None;
+    }
+
+    let mut i = whitespace(bytes, i);
+    if let Some(b']') = at(bytes, i) {
+        return Some(i + 1);
+    }
+
+    loop {
+        i = match value(bytes, i, depth) {
+            Some(end) => whitespace(bytes, end),
+            None => return None,
+        };
+
+        match at(bytes, i) {
+            Some(b',') => i += 1,
+            Some(b']') => return Some(i + 1),
+            _ => return None,
+        }
+    }
+}
+
+/// Parses the rest of a string whose opening quote is just before `i`.
+const fn string(bytes: &[u8], mut i: usize) -> Option<usize> {
+    while i < bytes.len() {
+        match bytes[i] {
+            b'"' => return Some(i + 1),
+            b'\\' => match at(bytes, i + 1) {
+                Some(b'"' | b'\\' | b'/' | b'b' | b'f' | b'n' | b'r' | b't') => i += 2,
+                Some(b'u') => {
+                    let mut digit = i + 2;
+                    while digit < i + 6 {
+                        match at(bytes, digit) {
+                            Some(b) if b.is_ascii_hexdigit() => digit += 1,
+                            _ => return None,
+                        }
+                    }
+                    i = digit;
+                }
+                _ => return None,
+            },
+            0x00..=0x1F => return None,
+            _ => i += 1,
+        }
+    }
+
+    None
+}
+
+const fn literal(bytes: &[u8], i: usize, lit: &[u8]) -> Option<usize> {
+    let mut j = 0;
+    while j < lit.len() {
+        match at(bytes, i + j) {
+            Some(b) if b == lit[j] => j += 1,
+            _ => return None,
+        }
+    }
+
+    Some(i + lit.len())
+}
+
+const fn whitespace(bytes: &[u8], mut i: usize) -> usize {
+    while i < bytes.len() && matches!(bytes[i], b' ' | b'\t' | b'\n' | b'\r') {
+        i += 1;
+    }
+    i
+}
+
+/// Parses a number starting at `i`, returning the index just after it.
+const fn number(bytes: &[u8], mut i: usize) -> Option<usize> {
+    if i < bytes.len() && bytes[i] == b'-' {
+        i += 1;
+    }
+
+    match at(bytes, i) {
+        Some(b'0') => i += 1,
+        Some(b'1'..=b'9') => i = digits(bytes, i),
+        _ => return None,
+    }
+
+    if let Some(b'.') = at(bytes, i) {
+        let start = i + 1;
+        i = digits(bytes, start);
+        if i == start {
+            return None;
+        }
+    }
+
+    if let Some(b'e' | b'E') = at(bytes, i) {
+        i += 1;
+        if let Some(b'+' | b'-') = at(bytes, i) {
+            i += 1;
+        }
+
+        let start = i;
+        i = digits(bytes, start);
+        if i == start {
+            return None;
+        }
+    }
+
+    Some(i)
+}
+
+const fn digits(bytes: &[u8], mut i: usize) -> usize {
+    while i < bytes.len() && bytes[i].is_ascii_digit() {
+        i += 1;
+    }
+    i
+}
+
+#[inline(always)]
+const fn at(bytes: &[u8], i: usize) -> Option<u8> {
+    if i < bytes.len() {
+        Some(bytes[i])
+    } else {
+        None
+    }
+}
+
+/// The length of `bytes` once [minify](minify)'d, leaving any holes as they are.
+pub const fn minified_len(bytes: &[u8]) -> usize {
+    minify::<0, 0>(bytes, Holes::Keep).len
+}
+
+/// What [minify](minify) does with holes: parenthesized groups outside of strings, like `(name: &str)` in a [template!](crate::template).
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Holes {
+    Keep,
+    Remove,
+    Null,
+}
+
+pub struct Minified<const N: usize, const H: usize> {
+    pub text: [u8; N],
+    pub len: usize,
+    pub holes: [usize; H],
+    pub hole_count: usize,
+}
+
+/// Strips whitespace from `bytes` outside of strings, only keeping a single space between tokens that would otherwise run together (like `1 2`), so invalid JSON stays invalid.
+///
+/// Writes as much as fits into an array of `N` bytes, and records where up to `H` holes were found.
+pub const fn minify<const N: usize, const H: usize>(bytes: &[u8], holes: Holes) -> Minified<N, H> {
+    let mut out = Minified {
+        text: [0; N],
+        len: 0,
+        holes: [0; H],
+        hole_count: 0,
+    };
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut space = false;
+    let mut prev = b' ';
+
+    let mut i = 0;
+    while i < bytes.len() {
+        let b = bytes[i];
+        i += 1;
+
+        if in_string {
+            match (escaped, b) {
+                (true, _) => escaped = false,
+                (false, b'\\') => escaped = true,
+                (false, b'"') => in_string = false,
+                _ => {}
+            }
+        } else if matches!(b, b' ' | b'\t' | b'\n' | b'\r') {
+            space = true;
+            continue;
+        } else if b == b'(' && !matches!(holes, Holes::Keep) {
+            let mut depth = 1;
+            while i < bytes.len() && depth > 0 {
+                match bytes[i] {
+                    b'(' => depth += 1,
+                    b')' => depth -= 1,
+                    _ => {}
+                }
+                i += 1;
+            }
+
+            if out.hole_count < H {
+                out.holes[out.hole_count] = out.len;
+            }
+            out.hole_count += 1;
+            if let Holes::Null = holes {
+                let mut j = 0;
+                while j < 4 {
+                    out = push(out, b"null"[j]);
+                    j += 1;
+                }
+            }
+
+            space = false;
+            prev = b'l';
+            continue;
+        } else {
+            if space && is_word(prev) && is_word(b) {
+                out = push(out, b' ');
+            }
+            in_string = b == b'"';
+        }
+
+        space = false;
+        prev = b;
+        out = push(out, b);
+    }
+
+    out
+}
+
+#[inline(always)]
+const fn push<const N: usize, const H: usize>(mut out: Minified<N, H>, b: u8) -> Minified<N, H> {
+    if out.len < N {
+        out.text[out.len] = b;
+    }
+    out.len += 1;
+    out
+}
+
+/// Checks that minified `bytes` are one valid JSON value, for [json_str!](crate::json_str).
+pub const fn minified_str(bytes: &[u8]) -> &str {
+    assert!(is_value(bytes), "not a single valid JSON value!");
+    match core::str::from_utf8(bytes) {
+        Ok(s) => s,
+        Err(_) => panic!("not a single valid JSON value!"),
+    }
+}
+
+const fn is_word(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || matches!(b, b'.' | b'_')
+}
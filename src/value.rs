@@ -0,0 +1,630 @@
+use alloc::{borrow::Cow, boxed::Box, collections::BTreeMap, string::String, vec, vec::Vec};
+use core::{
+    fmt, mem,
+    ops::{Index, IndexMut},
+};
+
+use crate::{ArrayWriter, JsonBuffer, ObjectWriter, WriteToJson, WriteToJsonRef};
+
+/// An owned JSON document, for when it has to be put together out of order or looked at before it's written.
+///
+/// Objects keep their keys in the order they were inserted. Indexing into a value that isn't there (or isn't an object or array) gives back `Null`, and assigning to a missing key of an object (or of `Null`) inserts it.
+/// # Examples
+/// ```
+/// use nyoom_json::{Map, Serializer, Value};
+///
+/// let mut cat = Value::from(
+///     Map::new()
+///         .with("name", "mittens")
+///         .with("lives", 9)
+///         .with("toys", vec!["mouse", "ball"]),
+/// );
+///
+/// cat["lives"] = Value::from(cat["lives"].as_i64().unwrap() - 1);
+/// cat["toys"].as_array_mut().unwrap().push("box".into());
+/// cat["owner"]["name"] = "emily".into();
+/// assert_eq!(cat["illness"], Value::Null);
+///
+/// let mut out = String::new();
+/// let mut ser = Serializer::new(&mut out);
+///
+/// let mut obj = ser.object();
+/// obj.field("cat", &cat);
+/// obj.field("fed", true);
+/// obj.end();
+///
+/// assert_eq!(
+///     out,
+///     r#"{"cat":{"name":"mittens","lives":8,"toys":["mouse","ball","box"],"owner":{"name":"emily"}},"fed":true}"#
+/// );
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum Value {
+    #[default]
+    Null,
+    Bool(bool),
+    Number(Number),
+    String(String),
+    Array(Vec<Value>),
+    Object(Map),
+}
+
+impl Value {
+    #[inline]
+    pub fn is_null(&self) -> bool {
+        matches!(self, Value::Null)
+    }
+
+    #[inline]
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Value::Bool(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    pub fn as_number(&self) -> Option<&Number> {
+        match self {
+            Value::Number(n) => Some(n),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    pub fn as_u64(&self) -> Option<u64> {
+        self.as_number().and_then(Number::as_u64)
+    }
+
+    #[inline]
+    pub fn as_i64(&self) -> Option<i64> {
+        self.as_number().and_then(Number::as_i64)
+    }
+
+    #[inline]
+    pub fn as_f64(&self) -> Option<f64> {
+        self.as_number().map(Number::as_f64)
+    }
+
+    #[inline]
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    pub fn as_array(&self) -> Option<&Vec<Value>> {
+        match self {
+            Value::Array(arr) => Some(arr),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    pub fn as_array_mut(&mut self) -> Option<&mut Vec<Value>> {
+        match self {
+            Value::Array(arr) => Some(arr),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    pub fn as_object(&self) -> Option<&Map> {
+        match self {
+            Value::Object(map) => Some(map),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    pub fn as_object_mut(&mut self) -> Option<&mut Map> {
+        match self {
+            Value::Object(map) => Some(map),
+            _ => None,
+        }
+    }
+
+    /// Looks up a key, if this is an object.
+    #[inline]
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.as_object().and_then(|map| map.get(key))
+    }
+
+    /// Looks up a key, if this is an object.
+    #[inline]
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut Value> {
+        self.as_object_mut().and_then(|map| map.get_mut(key))
+    }
+
+    /// Replaces this value with `Null`, returning what was there.
+    #[inline]
+    pub fn take(&mut self) -> Value {
+        mem::take(self)
+    }
+}
+
+static NULL: Value = Value::Null;
+
+impl Index<&str> for Value {
+    type Output = Value;
+
+    fn index(&self, key: &str) -> &Value {
+        self.get(key).unwrap_or(&NULL)
+    }
+}
+
+impl IndexMut<&str> for Value {
+    /// # Panics
+    ///
+    /// Panics if this is neither an object nor `Null`.
+    fn index_mut(&mut self, key: &str) -> &mut Value {
+        if self.is_null() {
+            *self = Value::Object(Map::new());
+        }
+
+        match self {
+            Value::Object(map) => map.entry(key),
+            _ => panic!("can only index into an object with a string"),
+        }
+    }
+}
+
+impl Index<usize> for Value {
+    type Output = Value;
+
+    fn index(&self, i: usize) -> &Value {
+        self.as_array().and_then(|arr| arr.get(i)).unwrap_or(&NULL)
+    }
+}
+
+impl IndexMut<usize> for Value {
+    /// # Panics
+    ///
+    /// Panics if this isn't an array, or `i` is out of bounds.
+    fn index_mut(&mut self, i: usize) -> &mut Value {
+        match self {
+            Value::Array(arr) => &mut arr[i],
+            _ => panic!("can only index into an array with an integer"),
+        }
+    }
+}
+
+impl<S: JsonBuffer> WriteToJsonRef<S> for Value {
+    fn write_to_json_ref(&self, out: &mut S) {
+        match self {
+            Value::Null => out.push_str("null"),
+            Value::Bool(b) => b.write_to_json(out),
+            Value::Number(n) => n.write_to_json_ref(out),
+            Value::String(s) => s.write_to_json_ref(out),
+            Value::Array(arr) => ArrayWriter::start(out).extend(arr),
+            Value::Object(map) => map.write_to_json_ref(out),
+        }
+    }
+}
+
+impl<S: JsonBuffer> WriteToJson<S> for Value {
+    #[inline(always)]
+    fn write_to_json(self, out: &mut S) {
+        self.write_to_json_ref(out)
+    }
+}
+
+impl From<bool> for Value {
+    #[inline]
+    fn from(b: bool) -> Value {
+        Value::Bool(b)
+    }
+}
+
+impl From<Number> for Value {
+    #[inline]
+    fn from(n: Number) -> Value {
+        Value::Number(n)
+    }
+}
+
+macro_rules! impl_from_number {
+    ($($ty:ty),*) => {
+        $(
+            impl From<$ty> for Value {
+                #[inline]
+                fn from(n: $ty) -> Value {
+                    Value::Number(n.into())
+                }
+            }
+        )*
+    }
+}
+
+impl_from_number!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize, f32, f64);
+
+impl From<&str> for Value {
+    #[inline]
+    fn from(s: &str) -> Value {
+        Value::String(s.into())
+    }
+}
+
+impl From<String> for Value {
+    #[inline]
+    fn from(s: String) -> Value {
+        Value::String(s)
+    }
+}
+
+impl From<Cow<'_, str>> for Value {
+    #[inline]
+    fn from(s: Cow<'_, str>) -> Value {
+        Value::String(s.into_owned())
+    }
+}
+
+impl From<Box<str>> for Value {
+    #[inline]
+    fn from(s: Box<str>) -> Value {
+        Value::String(s.into())
+    }
+}
+
+impl From<char> for Value {
+    #[inline]
+    fn from(c: char) -> Value {
+        Value::String(c.into())
+    }
+}
+
+impl From<Map> for Value {
+    #[inline]
+    fn from(map: Map) -> Value {
+        Value::Object(map)
+    }
+}
+
+impl From<()> for Value {
+    #[inline]
+    fn from(_: ()) -> Value {
+        Value::Null
+    }
+}
+
+/// `None` becomes `Null`.
+impl<T: Into<Value>> From<Option<T>> for Value {
+    #[inline]
+    fn from(val: Option<T>) -> Value {
+        val.map_or(Value::Null, Into::into)
+    }
+}
+
+impl<T: Into<Value>> From<Vec<T>> for Value {
+    #[inline]
+    fn from(vals: Vec<T>) -> Value {
+        vals.into_iter().collect()
+    }
+}
+
+impl<T: Into<Value> + Clone> From<&[T]> for Value {
+    #[inline]
+    fn from(vals: &[T]) -> Value {
+        vals.iter().cloned().collect()
+    }
+}
+
+impl<T: Into<Value>, const N: usize> From<[T; N]> for Value {
+    #[inline]
+    fn from(vals: [T; N]) -> Value {
+        vals.into_iter().collect()
+    }
+}
+
+/// Collects into an array.
+impl<T: Into<Value>> FromIterator<T> for Value {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Value {
+        Value::Array(iter.into_iter().map(Into::into).collect())
+    }
+}
+
+/// Collects into an object.
+impl<K: Into<String>, V: Into<Value>> FromIterator<(K, V)> for Value {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Value {
+        Value::Object(iter.into_iter().collect())
+    }
+}
+
+/// A JSON number: an unsigned or negative integer, or a float.
+///
+/// Integers are written the same way the integer types are, respecting [Options::large_ints_as_strings](crate::Options::large_ints_as_strings), and floats that aren't finite are written as `null`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Number(N);
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum N {
+    PosInt(u64),
+    NegInt(i64),
+    Float(f64),
+}
+
+impl Number {
+    /// The number as a `u64`, if it is a positive integer (or zero).
+    #[inline]
+    pub fn as_u64(&self) -> Option<u64> {
+        match self.0 {
+            N::PosInt(n) => Some(n),
+            _ => None,
+        }
+    }
+
+    /// The number as an `i64`, if it is an integer that fits.
+    #[inline]
+    pub fn as_i64(&self) -> Option<i64> {
+        match self.0 {
+            N::PosInt(n) => i64::try_from(n).ok(),
+            N::NegInt(n) => Some(n),
+            N::Float(_) => None,
+        }
+    }
+
+    /// The number as an `f64`, which may lose precision for large integers.
+    #[inline]
+    pub fn as_f64(&self) -> f64 {
+        match self.0 {
+            N::PosInt(n) => n as f64,
+            N::NegInt(n) => n as f64,
+            N::Float(n) => n,
+        }
+    }
+
+    #[inline]
+    pub fn is_f64(&self) -> bool {
+        matches!(self.0, N::Float(_))
+    }
+}
+
+macro_rules! impl_number_from {
+    (unsigned: $($uty:ty),*; signed: $($ity:ty),*; float: $($fty:ty),*) => {
+        $(
+            impl From<$uty> for Number {
+                #[inline]
+                fn from(n: $uty) -> Number {
+                    Number(N::PosInt(n as u64))
+                }
+            }
+        )*
+        $(
+            impl From<$ity> for Number {
+                #[inline]
+                fn from(n: $ity) -> Number {
+                    match n < 0 {
+                        true => Number(N::NegInt(n as i64)),
+                        false => Number(N::PosInt(n as u64)),
+                    }
+                }
+            }
+        )*
+        $(
+            impl From<$fty> for Number {
+                #[inline]
+                fn from(n: $fty) -> Number {
+                    Number(N::Float(n as f64))
+                }
+            }
+        )*
+    }
+}
+
+impl_number_from!(unsigned: u8, u16, u32, u64, usize; signed: i8, i16, i32, i64, isize; float: f32, f64);
+
+impl<S: JsonBuffer> WriteToJsonRef<S> for Number {
+    #[inline]
+    fn write_to_json_ref(&self, out: &mut S) {
+        match self.0 {
+            N::PosInt(n) => n.write_to_json(out),
+            N::NegInt(n) => n.write_to_json(out),
+            N::Float(n) => n.write_to_json(out),
+        }
+    }
+}
+
+impl<S: JsonBuffer> WriteToJson<S> for Number {
+    #[inline(always)]
+    fn write_to_json(self, out: &mut S) {
+        self.write_to_json_ref(out)
+    }
+}
+
+/// The fields of an object [Value](Value), kept in the order they were first inserted.
+///
+/// Keys are also kept in a sorted index, so [insert](Map::insert), [get](Map::get) and [entry](Map::entry) take `O(log n)` and collecting `n` fields takes `O(n log n)`. [remove](Map::remove) takes `O(n)`, since every field after the removed one moves down a place. The index holds its own copy of each key.
+/// # Examples
+/// ```
+/// use nyoom_json::{Map, Value};
+///
+/// let mut map = Map::new().with("name", "mittens").with("lives", 9);
+/// map.insert("name", "beans");
+/// map.insert("fed", true);
+///
+/// assert_eq!(map.keys().collect::<Vec<_>>(), ["name", "lives", "fed"]);
+/// assert_eq!(map.remove("lives"), Some(Value::from(9)));
+/// assert_eq!(map.get("name").and_then(Value::as_str), Some("beans"));
+/// ```
+#[derive(Clone, Default, PartialEq)]
+pub struct Map {
+    entries: Vec<(String, Value)>,
+    // position of each key in `entries`
+    index: BTreeMap<String, usize>,
+}
+
+impl Map {
+    #[inline]
+    pub fn new() -> Map {
+        Map::default()
+    }
+
+    /// Makes an empty map with room for `capacity` fields before the field list reallocates.
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> Map {
+        Map {
+            entries: Vec::with_capacity(capacity),
+            index: BTreeMap::new(),
+        }
+    }
+
+    /// Inserts a field, for building maps in a single expression.
+    #[inline]
+    pub fn with(mut self, key: impl Into<String>, val: impl Into<Value>) -> Map {
+        self.insert(key, val);
+        self
+    }
+
+    /// Inserts a field, returning the previous value if the key was already there. Existing keys keep their place.
+    pub fn insert(&mut self, key: impl Into<String>, val: impl Into<Value>) -> Option<Value> {
+        let key = key.into();
+        let val = val.into();
+        match self.index.get(&key) {
+            Some(&i) => Some(mem::replace(&mut self.entries[i].1, val)),
+            None => {
+                self.index.insert(key.clone(), self.entries.len());
+                self.entries.push((key, val));
+                None
+            }
+        }
+    }
+
+    /// Removes a field, keeping the other fields in order.
+    pub fn remove(&mut self, key: &str) -> Option<Value> {
+        let i = self.index.remove(key)?;
+        for pos in self.index.values_mut() {
+            if *pos > i {
+                *pos -= 1;
+            }
+        }
+
+        Some(self.entries.remove(i).1)
+    }
+
+    #[inline]
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.index.get(key).map(|&i| &self.entries[i].1)
+    }
+
+    #[inline]
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut Value> {
+        self.index.get(key).map(|&i| &mut self.entries[i].1)
+    }
+
+    /// Gets a field, inserting `Null` if it isn't there yet.
+    pub fn entry(&mut self, key: &str) -> &mut Value {
+        let i = match self.index.get(key) {
+            Some(&i) => i,
+            None => {
+                let i = self.entries.len();
+                self.index.insert(key.into(), i);
+                self.entries.push((key.into(), Value::Null));
+                i
+            }
+        };
+
+        &mut self.entries[i].1
+    }
+
+    #[inline]
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.index.contains_key(key)
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Value)> {
+        self.entries.iter().map(|(k, v)| (k.as_str(), v))
+    }
+
+    #[inline]
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (&str, &mut Value)> {
+        self.entries.iter_mut().map(|(k, v)| (k.as_str(), v))
+    }
+
+    #[inline]
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.entries.iter().map(|(k, _)| k.as_str())
+    }
+
+    #[inline]
+    pub fn values(&self) -> impl Iterator<Item = &Value> {
+        self.entries.iter().map(|(_, v)| v)
+    }
+}
+
+impl fmt::Debug for Map {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl Index<&str> for Map {
+    type Output = Value;
+
+    fn index(&self, key: &str) -> &Value {
+        self.get(key).unwrap_or(&NULL)
+    }
+}
+
+impl IndexMut<&str> for Map {
+    fn index_mut(&mut self, key: &str) -> &mut Value {
+        self.entry(key)
+    }
+}
+
+impl<K: Into<String>, V: Into<Value>> FromIterator<(K, V)> for Map {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Map {
+        let mut map = Map::new();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<K: Into<String>, V: Into<Value>> Extend<(K, V)> for Map {
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        self.entries.reserve(iter.size_hint().0);
+        for (k, v) in iter {
+            self.insert(k, v);
+        }
+    }
+}
+
+impl IntoIterator for Map {
+    type Item = (String, Value);
+    type IntoIter = vec::IntoIter<(String, Value)>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.into_iter()
+    }
+}
+
+impl<S: JsonBuffer> WriteToJsonRef<S> for Map {
+    #[inline]
+    fn write_to_json_ref(&self, out: &mut S) {
+        let mut obj = ObjectWriter::start(out);
+        for (k, v) in &self.entries {
+            obj.field(k.as_str(), v);
+        }
+    }
+}
+
+impl<S: JsonBuffer> WriteToJson<S> for Map {
+    #[inline(always)]
+    fn write_to_json(self, out: &mut S) {
+        self.write_to_json_ref(out)
+    }
+}
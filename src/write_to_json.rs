@@ -1,10 +1,137 @@
-use crate::{escape::escape_str, JsonBuffer};
+#[cfg(feature = "alloc")]
+use alloc::{
+    borrow::{Cow, ToOwned},
+    boxed::Box,
+    rc::Rc,
+    string::String,
+    sync::Arc,
+};
+
+use core::{
+    fmt::{self, Write},
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6},
+    num::{
+        NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroIsize, NonZeroU128,
+        NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU8, NonZeroUsize, Wrapping,
+    },
+};
+
+use crate::{
+    escape::{escape_str, Escaper, Verbatim},
+    JsonBuffer,
+};
 
 /// A value that is able to be written directly into JSON.
 pub trait WriteToJson<S: JsonBuffer> {
     fn write_to_json(self, out: &mut S);
 }
 
+/// A value that is able to be written into JSON by reference.
+///
+/// A reference to any type implementing this trait is [WriteToJson](WriteToJson), so values that are expensive or impossible to copy can be passed by reference to `field`, `add` and `extend`.
+/// # Examples
+/// ```
+/// use nyoom_json::{JsonBuffer, Serializer, WriteToJsonRef};
+///
+/// struct Cat {
+///     name: String,
+///     lives: u8,
+/// }
+///
+/// impl<S: JsonBuffer> WriteToJsonRef<S> for Cat {
+///     fn write_to_json_ref(&self, out: &mut S) {
+///         let mut ser = Serializer::new(out);
+///         let mut obj = ser.object();
+///         obj.field("name", &self.name);
+///         obj.field("lives", self.lives);
+///     }
+/// }
+///
+/// let cats = vec![
+///     Cat { name: "mittens".to_string(), lives: 9 },
+///     Cat { name: "beans".to_string(), lives: 8 },
+/// ];
+///
+/// let mut out = String::new();
+/// let mut ser = Serializer::new(&mut out);
+///
+/// let mut arr = ser.array();
+/// arr.add(&cats[0]);
+/// arr.extend(&cats[1..]);
+/// arr.end();
+///
+/// assert_eq!(out, r#"[{"name":"mittens","lives":9},{"name":"beans","lives":8}]"#);
+/// ```
+///
+/// # Migrating `Copy` types
+/// `&T` used to be [WriteToJson](WriteToJson) for any `T: Copy + WriteToJson`. It now needs `T: WriteToJsonRef`, since the two blanket impls can't coexist.
+/// A `Copy` type that only implements [WriteToJson](WriteToJson) can be passed by reference again by forwarding to its by-value impl:
+/// ```
+/// use nyoom_json::{JsonBuffer, Serializer, WriteToJson, WriteToJsonRef};
+///
+/// #[derive(Clone, Copy)]
+/// struct Lives(u8);
+///
+/// impl<S: JsonBuffer> WriteToJson<S> for Lives {
+///     fn write_to_json(self, out: &mut S) {
+///         self.0.write_to_json(out)
+///     }
+/// }
+///
+/// impl<S: JsonBuffer> WriteToJsonRef<S> for Lives {
+///     fn write_to_json_ref(&self, out: &mut S) {
+///         (*self).write_to_json(out)
+///     }
+/// }
+///
+/// let mut out = String::new();
+/// let mut ser = Serializer::new(&mut out);
+///
+/// let mut arr = ser.array();
+/// arr.add(&Lives(9));
+/// arr.extend(&[Lives(8), Lives(7)]);
+/// arr.end();
+///
+/// assert_eq!(out, "[9,8,7]");
+/// ```
+pub trait WriteToJsonRef<S: JsonBuffer> {
+    fn write_to_json_ref(&self, out: &mut S);
+}
+
+impl<S: JsonBuffer, T> WriteToJson<S> for &T
+where
+    T: WriteToJsonRef<S> + ?Sized,
+{
+    #[inline(always)]
+    fn write_to_json(self, out: &mut S) {
+        self.write_to_json_ref(out)
+    }
+}
+
+impl<S: JsonBuffer, T> WriteToJsonRef<S> for &T
+where
+    T: WriteToJsonRef<S> + ?Sized,
+{
+    #[inline(always)]
+    fn write_to_json_ref(&self, out: &mut S) {
+        (**self).write_to_json_ref(out)
+    }
+}
+
+/// Implements [WriteToJsonRef] for `Copy` types by copying them out and writing them by value.
+macro_rules! impl_copy_ref {
+    ($($ty:ty),*) => {
+        $(
+            impl<S: JsonBuffer> WriteToJsonRef<S> for $ty {
+                #[inline(always)]
+                fn write_to_json_ref(&self, out: &mut S) {
+                    (*self).write_to_json(out)
+                }
+            }
+        )*
+    }
+}
+
 macro_rules! impl_int {
     ($($ty:ty),*) => {
         $(
@@ -15,38 +142,256 @@ macro_rules! impl_int {
                     out.push_str(int_buf.format(self));
                 }
             }
+
+            impl<S: JsonBuffer> WriteToJson<S> for SafeInt<$ty> {
+                #[inline(always)]
+                fn write_to_json(self, out: &mut S) {
+                    self.0.write_to_json(out)
+                }
+            }
+
+            impl_copy_ref!($ty, SafeInt<$ty>);
         )*
 
     }
 }
 
+/// The largest integer JavaScript can represent exactly, i.e `Number.MAX_SAFE_INTEGER`.
+const MAX_SAFE_INTEGER: u64 = (1 << 53) - 1;
+
+macro_rules! impl_large_int {
+    ($($ty:ty => $is_safe:expr),*) => {
+        $(
+            impl<S: JsonBuffer> WriteToJson<S> for $ty {
+                #[inline(always)]
+                fn write_to_json(self, out: &mut S) {
+                    match out.options().large_ints_as_strings {
+                        true => SafeInt(self).write_to_json(out),
+                        false => {
+                            let mut int_buf = itoa::Buffer::new();
+                            out.push_str(int_buf.format(self));
+                        }
+                    }
+                }
+            }
+
+            impl<S: JsonBuffer> WriteToJson<S> for SafeInt<$ty> {
+                #[inline(always)]
+                fn write_to_json(self, out: &mut S) {
+                    let mut int_buf = itoa::Buffer::new();
+                    let formatted = int_buf.format(self.0);
+                    let is_safe: fn($ty) -> bool = $is_safe;
+                    match is_safe(self.0) {
+                        true => out.push_str(formatted),
+                        false => {
+                            crate::cold();
+                            out.push('"');
+                            out.push_str(formatted);
+                            out.push('"');
+                        }
+                    }
+                }
+            }
+
+            impl_copy_ref!($ty, SafeInt<$ty>);
+        )*
+    }
+}
+
 macro_rules! impl_float {
     ($($ty:ty),*) => {
         $(
             impl<S: JsonBuffer> WriteToJson<S> for $ty {
                 #[inline(always)]
                 fn write_to_json(self, out: &mut S) {
-                    let mut float_buf = ryu::Buffer::new();
-                    out.push_str(float_buf.format(self));
+                    // JSON has no way to represent NaN or infinity
+                    match self.is_finite() {
+                        true => {
+                            let mut float_buf = ryu::Buffer::new();
+                            out.push_str(float_buf.format_finite(self));
+                        }
+                        false => {
+                            crate::cold();
+                            out.push_str("null");
+                        }
+                    }
                 }
             }
+
+            impl_copy_ref!($ty);
         )*
 
     }
 }
 
-impl_int!(u8, u16, u32, u64, i8, i16, i32, i64);
+impl_int!(u8, u16, u32, i8, i16, i32);
+impl_large_int!(
+    u64 => |v| v <= MAX_SAFE_INTEGER,
+    u128 => |v| v <= MAX_SAFE_INTEGER as u128,
+    i64 => |v| v.unsigned_abs() <= MAX_SAFE_INTEGER,
+    i128 => |v| v.unsigned_abs() <= MAX_SAFE_INTEGER as u128,
+    usize => |v| v as u128 <= MAX_SAFE_INTEGER as u128,
+    isize => |v| v.unsigned_abs() as u128 <= MAX_SAFE_INTEGER as u128
+);
 impl_float!(f32, f64);
 
-impl<S: JsonBuffer> WriteToJson<S> for &str {
+macro_rules! impl_wrapped_int {
+    ($($ty:ty),*) => {
+        $(
+            impl<S: JsonBuffer> WriteToJson<S> for $ty {
+                #[inline(always)]
+                fn write_to_json(self, out: &mut S) {
+                    self.get().write_to_json(out)
+                }
+            }
+
+            impl_copy_ref!($ty);
+        )*
+    }
+}
+
+impl_wrapped_int!(
+    NonZeroU8,
+    NonZeroU16,
+    NonZeroU32,
+    NonZeroU64,
+    NonZeroU128,
+    NonZeroUsize,
+    NonZeroI8,
+    NonZeroI16,
+    NonZeroI32,
+    NonZeroI64,
+    NonZeroI128,
+    NonZeroIsize
+);
+
+impl<S: JsonBuffer, T: WriteToJson<S>> WriteToJson<S> for Wrapping<T> {
+    #[inline(always)]
+    fn write_to_json(self, out: &mut S) {
+        self.0.write_to_json(out)
+    }
+}
+
+impl<S: JsonBuffer, T: WriteToJsonRef<S>> WriteToJsonRef<S> for Wrapping<T> {
+    #[inline(always)]
+    fn write_to_json_ref(&self, out: &mut S) {
+        self.0.write_to_json_ref(out)
+    }
+}
+
+impl<S: JsonBuffer> WriteToJson<S> for char {
     #[inline(always)]
     fn write_to_json(self, out: &mut S) {
+        self.encode_utf8(&mut [0; 4]).write_to_json_ref(out)
+    }
+}
+
+impl_copy_ref!(char);
+
+// addresses only ever display as digits, hex, dots, colons, brackets and `%`, so they don't need escaping
+macro_rules! impl_display_unescaped {
+    ($($ty:ty),*) => {
+        $(
+            impl<S: JsonBuffer> WriteToJson<S> for $ty {
+                #[inline]
+                fn write_to_json(self, out: &mut S) {
+                    out.push('"');
+                    let _ = write!(Verbatim(out), "{}", self);
+                    out.push('"');
+                }
+            }
+
+            impl_copy_ref!($ty);
+        )*
+    }
+}
+
+impl_display_unescaped!(
+    IpAddr,
+    Ipv4Addr,
+    Ipv6Addr,
+    SocketAddr,
+    SocketAddrV4,
+    SocketAddrV6
+);
+
+impl<S: JsonBuffer> WriteToJsonRef<S> for str {
+    #[inline(always)]
+    fn write_to_json_ref(&self, out: &mut S) {
         out.push('"');
         escape_str(self, out);
         out.push('"');
     }
 }
 
+#[cfg(feature = "alloc")]
+impl<S: JsonBuffer> WriteToJsonRef<S> for String {
+    #[inline(always)]
+    fn write_to_json_ref(&self, out: &mut S) {
+        self.as_str().write_to_json_ref(out)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<S: JsonBuffer> WriteToJson<S> for String {
+    #[inline(always)]
+    fn write_to_json(self, out: &mut S) {
+        self.as_str().write_to_json_ref(out)
+    }
+}
+
+macro_rules! impl_smart_pointer {
+    ($($ty:ident),*) => {
+        $(
+            #[cfg(feature = "alloc")]
+            impl<S: JsonBuffer, T> WriteToJsonRef<S> for $ty<T>
+            where
+                T: WriteToJsonRef<S> + ?Sized,
+            {
+                #[inline(always)]
+                fn write_to_json_ref(&self, out: &mut S) {
+                    (**self).write_to_json_ref(out)
+                }
+            }
+
+            #[cfg(feature = "alloc")]
+            impl<S: JsonBuffer, T> WriteToJson<S> for $ty<T>
+            where
+                T: WriteToJsonRef<S> + ?Sized,
+            {
+                #[inline(always)]
+                fn write_to_json(self, out: &mut S) {
+                    (*self).write_to_json_ref(out)
+                }
+            }
+        )*
+    }
+}
+
+impl_smart_pointer!(Box, Rc, Arc);
+
+#[cfg(feature = "alloc")]
+impl<S: JsonBuffer, T> WriteToJsonRef<S> for Cow<'_, T>
+where
+    T: WriteToJsonRef<S> + ToOwned + ?Sized,
+{
+    #[inline(always)]
+    fn write_to_json_ref(&self, out: &mut S) {
+        (**self).write_to_json_ref(out)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<S: JsonBuffer, T> WriteToJson<S> for Cow<'_, T>
+where
+    T: WriteToJsonRef<S> + ToOwned + ?Sized,
+{
+    #[inline(always)]
+    fn write_to_json(self, out: &mut S) {
+        (*self).write_to_json_ref(out)
+    }
+}
+
 impl<S: JsonBuffer> WriteToJson<S> for bool {
     #[inline(always)]
     fn write_to_json(self, out: &mut S) {
@@ -57,7 +402,29 @@ impl<S: JsonBuffer> WriteToJson<S> for bool {
     }
 }
 
+/// An integer that is written as a quoted decimal string if it falls outside of JavaScript's safe integer range (±(2^53 - 1)), and as a plain number otherwise.
+///
+/// This is the per-value version of [Options::large_ints_as_strings](crate::Options::large_ints_as_strings).
+/// # Examples
+/// ```
+/// use nyoom_json::{SafeInt, Serializer};
+///
+/// let mut out = String::new();
+/// let mut ser = Serializer::new(&mut out);
+///
+/// let mut obj = ser.object();
+/// obj.field("likes", SafeInt(42u64));
+/// obj.field("snowflake", SafeInt(1097585730145472512u64));
+/// obj.end();
+///
+/// assert_eq!(out, r#"{"likes":42,"snowflake":"1097585730145472512"}"#);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(transparent)]
+pub struct SafeInt<T>(pub T);
+
 /// The JSON null value!
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct Null;
 
 impl<S: JsonBuffer> WriteToJson<S> for () {
@@ -74,32 +441,38 @@ impl<S: JsonBuffer> WriteToJson<S> for Null {
     }
 }
 
-impl<S: JsonBuffer, T> WriteToJson<S> for &T
+impl_copy_ref!(bool, (), Null);
+
+impl<S: JsonBuffer, T> WriteToJson<S> for Option<T>
 where
-    T: Copy + WriteToJson<S>,
+    T: WriteToJson<S>,
 {
     #[inline(always)]
     fn write_to_json(self, out: &mut S) {
-        (*self).write_to_json(out)
+        match self {
+            Some(v) => v.write_to_json(out),
+            None => Null.write_to_json(out),
+        }
     }
 }
 
-impl<S: JsonBuffer, T> WriteToJson<S> for Option<T>
+impl<S: JsonBuffer, T> WriteToJsonRef<S> for Option<T>
 where
-    T: WriteToJson<S>,
+    T: WriteToJsonRef<S>,
 {
     #[inline(always)]
-    fn write_to_json(self, out: &mut S) {
+    fn write_to_json_ref(&self, out: &mut S) {
         match self {
-            Some(v) => v.write_to_json(out),
+            Some(v) => v.write_to_json_ref(out),
             None => Null.write_to_json(out),
         }
     }
 }
 
 /// A string that will *not* have escapes applied to it. You should only use this if you're *absolutely* sure you don't need them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[repr(transparent)]
-pub struct UnescapedStr<'a>(&'a str);
+pub struct UnescapedStr<'a>(pub(crate) &'a str);
 
 impl<'a> UnescapedStr<'a> {
     #[inline(always)]
@@ -123,3 +496,65 @@ impl<'a, S: JsonBuffer> WriteToJson<S> for UnescapedStr<'a> {
         out.push('"');
     }
 }
+
+impl_copy_ref!(UnescapedStr<'_>);
+
+/// A value that is written as a JSON string by way of its [Display](core::fmt::Display) implementation, escaping the formatter's output on the fly instead of going through a temporary `String`.
+///
+/// It can be used as an object key, too.
+/// # Examples
+/// ```
+/// use nyoom_json::{DisplayStr, Serializer};
+/// use std::net::Ipv4Addr;
+///
+/// let mut out = String::new();
+/// let mut ser = Serializer::new(&mut out);
+///
+/// let mut obj = ser.object();
+/// obj.field("address", DisplayStr(Ipv4Addr::LOCALHOST));
+/// obj.field(DisplayStr('🐈'), DisplayStr(1.5));
+/// obj.field("status", format_args!("fed {} times, last at \"{}\"", 3, "noon"));
+/// obj.end();
+///
+/// assert_eq!(out, r#"{"address":"127.0.0.1","🐈":"1.5","status":"fed 3 times, last at \"noon\""}"#);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct DisplayStr<T>(pub T);
+
+impl<S: JsonBuffer, T: fmt::Display> WriteToJson<S> for DisplayStr<T> {
+    #[inline(always)]
+    fn write_to_json(self, out: &mut S) {
+        self.write_to_json_ref(out)
+    }
+}
+
+impl<S: JsonBuffer, T: fmt::Display> WriteToJsonRef<S> for DisplayStr<T> {
+    #[inline]
+    fn write_to_json_ref(&self, out: &mut S) {
+        out.push('"');
+        let _ = write!(Escaper(out), "{}", self.0);
+        out.push('"');
+    }
+}
+
+impl<S: JsonBuffer> WriteToJson<S> for fmt::Arguments<'_> {
+    #[inline(always)]
+    fn write_to_json(self, out: &mut S) {
+        self.write_to_json_ref(out)
+    }
+}
+
+impl<S: JsonBuffer> WriteToJsonRef<S> for fmt::Arguments<'_> {
+    #[inline]
+    fn write_to_json_ref(&self, out: &mut S) {
+        match self.as_str() {
+            Some(s) => s.write_to_json_ref(out),
+            None => {
+                out.push('"');
+                let _ = Escaper(out).write_fmt(*self);
+                out.push('"');
+            }
+        }
+    }
+}
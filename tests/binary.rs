@@ -0,0 +1,74 @@
+mod common;
+
+use base64::engine::general_purpose::{STANDARD, STANDARD_NO_PAD, URL_SAFE, URL_SAFE_NO_PAD};
+use base64::Engine;
+use common::nyoom;
+use nyoom_json::{Base64, Hex};
+
+#[test]
+fn rfc4648_vectors() {
+    let vectors = [
+        ("", ""),
+        ("f", "Zg=="),
+        ("fo", "Zm8="),
+        ("foo", "Zm9v"),
+        ("foob", "Zm9vYg=="),
+        ("fooba", "Zm9vYmE="),
+        ("foobar", "Zm9vYmFy"),
+    ];
+
+    for (bytes, encoded) in vectors {
+        let bytes = bytes.as_bytes();
+        assert_eq!(nyoom(Base64::standard(bytes)), format!("\"{encoded}\""));
+        assert_eq!(
+            nyoom(Base64::standard_no_pad(bytes)),
+            format!("\"{}\"", encoded.trim_end_matches('='))
+        );
+    }
+}
+
+#[test]
+fn url_safe_alphabet() {
+    let bytes = [0xfb, 0xff, 0xbf, 0xfe];
+    assert_eq!(nyoom(Base64::standard(&bytes)), r#""+/+//g==""#);
+    assert_eq!(nyoom(Base64::url_safe(&bytes)), r#""-_-__g==""#);
+    assert_eq!(nyoom(Base64::url_safe_no_pad(&bytes)), r#""-_-__g""#);
+}
+
+#[test]
+fn matches_base64_crate() {
+    // long enough to go through the stack buffer several times, at every padding length
+    let bytes: Vec<u8> = (0..=255u8).cycle().take(1000).collect();
+    for len in 0..bytes.len() {
+        let bytes = &bytes[..len];
+        assert_eq!(
+            nyoom(Base64::standard(bytes)),
+            format!("\"{}\"", STANDARD.encode(bytes))
+        );
+        assert_eq!(
+            nyoom(Base64::standard_no_pad(bytes)),
+            format!("\"{}\"", STANDARD_NO_PAD.encode(bytes))
+        );
+        assert_eq!(
+            nyoom(Base64::url_safe(bytes)),
+            format!("\"{}\"", URL_SAFE.encode(bytes))
+        );
+        assert_eq!(
+            nyoom(Base64::url_safe_no_pad(bytes)),
+            format!("\"{}\"", URL_SAFE_NO_PAD.encode(bytes))
+        );
+    }
+}
+
+#[test]
+fn hex() {
+    let bytes: Vec<u8> = (0..=255u8).cycle().take(1000).collect();
+    let lower: String = bytes.iter().map(|b| format!("{b:02x}")).collect();
+
+    assert_eq!(nyoom(Hex::lower(&[])), r#""""#);
+    assert_eq!(nyoom(Hex::lower(&bytes)), format!("\"{lower}\""));
+    assert_eq!(
+        nyoom(Hex::upper(&bytes)),
+        format!("\"{}\"", lower.to_uppercase())
+    );
+}
@@ -0,0 +1,16 @@
+// each test crate only uses some of these
+#![allow(dead_code)]
+
+use nyoom_json::{Serializer, WriteToJson};
+
+/// Writes a single value into a fresh string.
+pub fn nyoom(value: impl WriteToJson<String>) -> String {
+    let mut out = String::new();
+    Serializer::new(&mut out).write(value);
+    out
+}
+
+/// Parses `out`, failing the test if it isn't valid JSON.
+pub fn parse(out: &str) -> serde_json::Value {
+    serde_json::from_str(out).unwrap_or_else(|e| panic!("{out:?} is not valid JSON: {e}"))
+}
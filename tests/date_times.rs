@@ -0,0 +1,58 @@
+#![cfg(any(feature = "chrono", feature = "time"))]
+
+mod common;
+
+use common::nyoom;
+
+/// Offsets that RFC 3339 can't represent, in seconds east of UTC.
+const SUB_MINUTE_OFFSETS: [i32; 4] = [19 * 60 + 32, -30, 1, -(5 * 3600 + 59)];
+
+#[test]
+#[cfg(feature = "chrono")]
+fn chrono_sub_minute_offsets() {
+    use chrono::{DateTime, FixedOffset, TimeZone, Utc};
+
+    let instant = Utc.timestamp_opt(0, 250_000_000).unwrap();
+    for offset in SUB_MINUTE_OFFSETS {
+        let time = instant.with_timezone(&FixedOffset::east_opt(offset).unwrap());
+        assert_eq!(
+            nyoom(time),
+            r#""1970-01-01T00:00:00.250Z""#,
+            "offset {offset}"
+        );
+    }
+
+    // whole minutes are kept, and every output is the same instant
+    for offset in [0, 60, -90 * 60, 14 * 3600, -1, 59] {
+        let time = instant.with_timezone(&FixedOffset::east_opt(offset).unwrap());
+        let out = nyoom(time);
+        assert_eq!(
+            DateTime::parse_from_rfc3339(out.trim_matches('"')).unwrap(),
+            instant,
+            "offset {offset}"
+        );
+    }
+    let time = instant.with_timezone(&FixedOffset::west_opt(90 * 60).unwrap());
+    assert_eq!(nyoom(time), r#""1969-12-31T22:30:00.250-01:30""#);
+}
+
+#[test]
+#[cfg(feature = "time")]
+fn time_sub_minute_offsets() {
+    use time::{OffsetDateTime, UtcOffset};
+
+    let instant = OffsetDateTime::from_unix_timestamp_nanos(250_000_000).unwrap();
+    for offset in SUB_MINUTE_OFFSETS {
+        let time = instant.to_offset(UtcOffset::from_whole_seconds(offset).unwrap());
+        assert_eq!(
+            nyoom(time),
+            r#""1970-01-01T00:00:00.250Z""#,
+            "offset {offset}"
+        );
+    }
+
+    let time = instant.to_offset(UtcOffset::from_hms(0, 19, 32).unwrap());
+    assert_eq!(nyoom(time), r#""1970-01-01T00:00:00.250Z""#);
+    let time = instant.to_offset(UtcOffset::from_hms(-1, -30, 0).unwrap());
+    assert_eq!(nyoom(time), r#""1969-12-31T22:30:00.250-01:30""#);
+}
@@ -0,0 +1,109 @@
+mod common;
+
+use std::sync::Arc;
+
+use common::parse;
+use nyoom_json::{DynWriteToJson, JsonBuffer, Options, Serializer, WithOptions, WriteToJsonRef};
+use serde_json::json;
+
+struct Cat {
+    name: &'static str,
+    lives: u64,
+}
+
+impl<S: JsonBuffer> WriteToJsonRef<S> for Cat {
+    fn write_to_json_ref(&self, out: &mut S) {
+        let mut ser = Serializer::new(out);
+        let mut obj = ser.object();
+        obj.field("name", self.name);
+        obj.field("lives", self.lives);
+    }
+}
+
+fn cat() -> Cat {
+    Cat {
+        name: "mittens",
+        lives: u64::MAX,
+    }
+}
+
+#[test]
+fn boxes_and_references() {
+    let records: Vec<Box<dyn DynWriteToJson>> = vec![
+        Box::new(cat()),
+        Box::new(-3),
+        Box::new("beans"),
+        Box::new(None::<u8>),
+        Box::new(vec![Some(1.5), None]),
+    ];
+    let borrowed: Vec<&dyn DynWriteToJson> = records.iter().map(|r| &**r).collect();
+
+    let mut out = String::new();
+    let mut ser = Serializer::new(&mut out);
+    let mut obj = ser.object();
+    obj.field("first", &*records[0]);
+    obj.complex_field("boxed", |ser| ser.array().extend(&records));
+    obj.complex_field("borrowed", |ser| ser.array().extend(borrowed));
+    obj.end();
+
+    let expected = json!([
+        { "name": "mittens", "lives": u64::MAX },
+        -3,
+        "beans",
+        null,
+        [1.5, null]
+    ]);
+    assert_eq!(
+        parse(&out),
+        json!({ "first": expected[0], "boxed": expected, "borrowed": expected })
+    );
+}
+
+#[test]
+fn send_and_sync() {
+    let send: Box<dyn DynWriteToJson + Send> = Box::new(1);
+    let sync: Box<dyn DynWriteToJson + Sync> = Box::new("two");
+    let shared: Arc<dyn DynWriteToJson + Send + Sync> = Arc::new(cat());
+
+    let handle = std::thread::spawn({
+        let shared = shared.clone();
+        move || {
+            let mut out = String::new();
+            let mut ser = Serializer::new(&mut out);
+            let mut arr = ser.array();
+            arr.add(&*send);
+            arr.add(&*shared);
+            arr.end();
+            out
+        }
+    });
+
+    let mut out = String::new();
+    let mut ser = Serializer::new(&mut out);
+    let mut arr = ser.array();
+    arr.add(&*sync);
+    arr.add(&*shared);
+    arr.end();
+
+    let cat = json!({ "name": "mittens", "lives": u64::MAX });
+    assert_eq!(parse(&handle.join().unwrap()), json!([1, cat]));
+    assert_eq!(parse(&out), json!(["two", cat]));
+}
+
+#[test]
+fn options_reach_erased_values() {
+    let record: Box<dyn DynWriteToJson> = Box::new(cat());
+
+    let mut out = String::new();
+    let mut buf = WithOptions::new(&mut out, Options::new().large_ints_as_strings(true));
+    let mut ser = Serializer::new(&mut buf);
+    let mut arr = ser.array();
+    arr.add(&*record);
+    arr.add(u64::MAX);
+    arr.end();
+
+    assert_eq!(
+        out,
+        format!(r#"[{{"name":"mittens","lives":"{0}"}},"{0}"]"#, u64::MAX)
+    );
+}
@@ -0,0 +1,67 @@
+use nyoom_json::{json_raw, json_str, RawJson};
+
+#[test]
+fn negative_numbers() {
+    assert_eq!(json_str!(-1), "-1");
+    assert_eq!(json_str!(-0), "-0");
+    assert_eq!(json_str!([-2, -3, -4.5]), "[-2,-3,-4.5]");
+    assert_eq!(json_str!({ "a": -1, "b": -0.25 }), r#"{"a":-1,"b":-0.25}"#);
+}
+
+#[test]
+fn exponents() {
+    assert_eq!(
+        json_str!([1e5, 1E5, 1e+5, 1e-5, 2.5e+10, -2.5E-10, 0e0]),
+        "[1e5,1E5,1e+5,1e-5,2.5e+10,-2.5E-10,0e0]"
+    );
+}
+
+#[test]
+fn nested_empty_containers() {
+    assert_eq!(json_str!([]), "[]");
+    assert_eq!(json_str!({}), "{}");
+    assert_eq!(
+        json_str!([[], {}, [[]], [{}], {"a": {}}]),
+        r#"[[],{},[[]],[{}],{"a":{}}]"#
+    );
+    assert_eq!(
+        json_str!({ "a": [], "b": { "c": [ { } ] } }),
+        r#"{"a":[],"b":{"c":[{}]}}"#
+    );
+}
+
+#[test]
+fn literals_and_strings() {
+    assert_eq!(json_str!(null), "null");
+    assert_eq!(json_str!([true, false, null]), "[true,false,null]");
+    // whitespace and punctuation inside strings is kept as is
+    assert_eq!(
+        json_str!(["a  b", " { [ , : ] } ", "tab\there", "é", "🐈"]),
+        r#"["a  b"," { [ , : ] } ","tab\there","é","🐈"]"#
+    );
+}
+
+#[test]
+fn parses_back() {
+    const DOC: &str = json_str!({
+        "numbers": [0, -1, 1.5, -1.5e-3, 1E+300],
+        "nested": [[{ "deep": [[], {}] }]],
+        "escapes": "quote \" backslash \\ newline \n"
+    });
+
+    let parsed: serde_json::Value = serde_json::from_str(DOC).unwrap();
+    assert_eq!(
+        parsed,
+        serde_json::json!({
+            "numbers": [0, -1, 1.5, -1.5e-3, 1e300],
+            "nested": [[{ "deep": [[], {}] }]],
+            "escapes": "quote \" backslash \\ newline \n"
+        })
+    );
+}
+
+#[test]
+fn raw() {
+    const RAW: RawJson<'static> = json_raw!([-1, 2e-2, {}]);
+    assert_eq!(RAW.as_str(), "[-1,2e-2,{}]");
+}
@@ -0,0 +1,69 @@
+mod common;
+
+use common::nyoom;
+use nyoom_json::{Map, Value};
+
+#[test]
+fn insert_keeps_first_position() {
+    let mut map = Map::new().with("a", 1).with("b", 2).with("c", 3);
+    assert_eq!(map.insert("b", 20), Some(Value::from(2)));
+    assert_eq!(map.insert("d", 4), None);
+
+    assert_eq!(nyoom(&map), r#"{"a":1,"b":20,"c":3,"d":4}"#);
+}
+
+#[test]
+fn lookups_after_remove() {
+    let mut map: Map = (0..10).map(|i| (i.to_string(), i)).collect();
+    assert_eq!(map.remove("3"), Some(Value::from(3)));
+    assert_eq!(map.remove("0"), Some(Value::from(0)));
+    assert_eq!(map.remove("3"), None);
+
+    assert_eq!(map.len(), 8);
+    for i in [1, 2, 4, 5, 6, 7, 8, 9] {
+        assert_eq!(map.get(&i.to_string()), Some(&Value::from(i)), "key {i}");
+    }
+    assert!(!map.contains_key("0"));
+
+    map.insert("9", "nine");
+    map["3"] = "three".into();
+    *map.get_mut("1").unwrap() = Value::Null;
+    assert_eq!(
+        nyoom(&map),
+        r#"{"1":null,"2":2,"4":4,"5":5,"6":6,"7":7,"8":8,"9":"nine","3":"three"}"#
+    );
+}
+
+#[test]
+fn collect_deduplicates() {
+    let map: Map = [("x", 1), ("y", 2), ("x", 3)].into_iter().collect();
+    assert_eq!(nyoom(&map), r#"{"x":3,"y":2}"#);
+
+    let mut map = map;
+    map.extend([("z", 4), ("y", 5)]);
+    assert_eq!(map.keys().collect::<Vec<_>>(), ["x", "y", "z"]);
+    assert_eq!(
+        map.values().collect::<Vec<_>>(),
+        [&Value::from(3), &Value::from(5), &Value::from(4)]
+    );
+}
+
+#[test]
+fn large_maps() {
+    let n = 100_000;
+    let map: Map = (0..n).map(|i| (format!("key{i}"), i)).collect();
+    assert_eq!(map.len(), n);
+    assert_eq!(map["key99999"], Value::from(99999));
+
+    let parsed: serde_json::Map<String, serde_json::Value> =
+        serde_json::from_str(&nyoom(&map)).unwrap();
+    assert_eq!(parsed.len(), n);
+}
+
+#[test]
+fn equality_ignores_history() {
+    let mut a = Map::new().with("a", 1).with("gone", 0).with("b", 2);
+    a.remove("gone");
+    assert_eq!(a, Map::new().with("a", 1).with("b", 2));
+    assert_ne!(a, Map::new().with("b", 2).with("a", 1));
+}
@@ -0,0 +1,128 @@
+#![cfg(feature = "std")]
+
+mod common;
+
+use std::io::{self, Read};
+
+use common::parse;
+use nyoom_json::{InvalidUtf8, ReadStr, Serializer};
+
+/// Hands out at most `chunk` bytes per read, with an `Interrupted` error before every other one.
+struct Trickle<'a> {
+    bytes: &'a [u8],
+    chunk: usize,
+    interrupt: bool,
+}
+
+impl<'a> Trickle<'a> {
+    fn new(bytes: &'a [u8], chunk: usize) -> Trickle<'a> {
+        Trickle {
+            bytes,
+            chunk,
+            interrupt: false,
+        }
+    }
+}
+
+impl Read for Trickle<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.interrupt = !self.interrupt;
+        if self.interrupt {
+            return Err(io::ErrorKind::Interrupted.into());
+        }
+
+        let len = self.chunk.min(buf.len()).min(self.bytes.len());
+        buf[..len].copy_from_slice(&self.bytes[..len]);
+        self.bytes = &self.bytes[len..];
+        Ok(len)
+    }
+}
+
+fn read_str<R: Read>(reader: R, invalid: InvalidUtf8) -> (String, io::Result<u64>) {
+    let mut out = String::new();
+    let mut ser = Serializer::new(&mut out);
+    let mut s = ser.string();
+    let result = s.read_from(reader, invalid);
+    s.end();
+    (out, result)
+}
+
+const TEXT: &str = "cats: 🐈 🐈‍⬛, \"quotes\", ünïcödé, 日本の猫, control \u{1} and \n";
+
+#[test]
+fn characters_split_across_reads() {
+    let expected = serde_json::to_string(TEXT).unwrap();
+    for chunk in 1..=8 {
+        let (out, result) = read_str(Trickle::new(TEXT.as_bytes(), chunk), InvalidUtf8::Error);
+        assert_eq!(out, expected, "{chunk} bytes per read");
+        assert_eq!(result.unwrap(), TEXT.len() as u64);
+    }
+}
+
+#[test]
+fn characters_split_across_the_buffer() {
+    // pad each character so it straddles the 8 KiB read buffer at a different offset
+    for pad in 8 * 1024 - 4..=8 * 1024 {
+        let text = "a".repeat(pad) + "🐈é日" + &"b".repeat(8 * 1024) + "🐟";
+        let (out, result) = read_str(text.as_bytes(), InvalidUtf8::Error);
+        assert_eq!(
+            out,
+            serde_json::to_string(&text).unwrap(),
+            "{pad} bytes before"
+        );
+        assert_eq!(result.unwrap(), text.len() as u64);
+    }
+}
+
+#[test]
+fn invalid_sequences() {
+    let inputs: [&[u8]; 5] = [
+        b"bad \xFF byte",
+        b"overlong \xC0\xAF and surrogate \xED\xA0\x80",
+        b"truncated \xF0\x9F\x90 in the middle",
+        b"truncated at the end \xF0\x9F\x90",
+        b"\xE6\x97",
+    ];
+
+    for input in inputs {
+        let lossy = String::from_utf8_lossy(input);
+        for chunk in [1, 2, 3, 64] {
+            let (out, _) = read_str(Trickle::new(input, chunk), InvalidUtf8::Replace);
+            assert_eq!(out, serde_json::to_string(&lossy).unwrap());
+
+            let skipped = lossy.replace(char::REPLACEMENT_CHARACTER, "");
+            let (out, _) = read_str(Trickle::new(input, chunk), InvalidUtf8::Skip);
+            assert_eq!(out, serde_json::to_string(&skipped).unwrap());
+
+            let (out, result) = read_str(Trickle::new(input, chunk), InvalidUtf8::Error);
+            assert_eq!(result.unwrap_err().kind(), io::ErrorKind::InvalidData);
+            let valid = &lossy[..lossy.find(char::REPLACEMENT_CHARACTER).unwrap()];
+            assert_eq!(out, serde_json::to_string(valid).unwrap());
+        }
+    }
+}
+
+#[test]
+fn errors_end_the_string() {
+    struct Fails<'a>(&'a [u8]);
+
+    impl Read for Fails<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            match self.0.is_empty() {
+                true => Err(io::ErrorKind::BrokenPipe.into()),
+                false => self.0.read(buf),
+            }
+        }
+    }
+
+    let mut error = None;
+    let mut out = String::new();
+    let mut ser = Serializer::new(&mut out);
+    let mut arr = ser.array();
+    arr.add(ReadStr::new(Fails("mew 🐈".as_bytes())).report_errors(&mut error));
+    arr.add(ReadStr::new(Trickle::new(b"ok", 1)));
+    arr.end();
+
+    assert_eq!(error.unwrap().kind(), io::ErrorKind::BrokenPipe);
+    assert_eq!(parse(&out), serde_json::json!(["mew 🐈", "ok"]));
+}
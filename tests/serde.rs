@@ -0,0 +1,218 @@
+#![cfg(feature = "serde")]
+
+mod common;
+
+use std::collections::BTreeMap;
+
+use common::{nyoom, parse};
+use nyoom_json::{Serde, Serializer, SingleValueSerializer};
+use serde::Serialize;
+
+fn assert_same<T: Serialize + ?Sized>(value: &T) {
+    assert_eq!(nyoom(Serde(value)), serde_json::to_string(value).unwrap());
+}
+
+#[derive(Serialize)]
+struct Unit;
+
+#[derive(Serialize, PartialEq, Eq, PartialOrd, Ord)]
+struct Newtype(u8);
+
+#[derive(Serialize)]
+struct Tuple(u8, &'static str);
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Cat {
+    name: &'static str,
+    favourite_toys: Vec<&'static str>,
+    owner: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    illness: Option<&'static str>,
+    #[serde(flatten)]
+    extra: BTreeMap<&'static str, u8>,
+}
+
+#[derive(Serialize)]
+enum Mood {
+    Sleepy,
+    Hungry(u8),
+    Grumpy(u8, bool),
+    Playful { toy: &'static str },
+}
+
+#[derive(Serialize)]
+#[serde(tag = "type")]
+enum Internal {
+    Ball { bounciness: f32 },
+}
+
+#[derive(Serialize)]
+#[serde(tag = "t", content = "c")]
+enum Adjacent {
+    Mouse(u8),
+}
+
+#[derive(Serialize)]
+#[serde(untagged)]
+enum Untagged {
+    Number(i64),
+    Text(&'static str),
+}
+
+#[test]
+fn primitives() {
+    assert_same(&true);
+    assert_same(&-12i8);
+    assert_same(&u64::MAX);
+    assert_same(&i128::MIN);
+    assert_same(&u128::MAX);
+    assert_same(&'\n');
+    assert_same("quotes \" and \\ and \u{1} and 🐈");
+    assert_same(&());
+    assert_same(&None::<u8>);
+    assert_same(&Some(5));
+}
+
+#[test]
+fn floats() {
+    for f in [
+        0.0,
+        -0.0,
+        0.1,
+        1.5,
+        -2.5e-8,
+        1e16,
+        1.5e16,
+        1e21,
+        1e300,
+        f64::MAX,
+        f64::MIN_POSITIVE,
+        5e-324,
+        f64::NAN,
+        f64::INFINITY,
+        f64::NEG_INFINITY,
+    ] {
+        assert_same(&f);
+    }
+
+    for f in [0.3f32, 1e30, f32::MAX, f32::NAN, f32::NEG_INFINITY] {
+        assert_same(&f);
+    }
+}
+
+#[test]
+fn structs_and_enums() {
+    assert_same(&Unit);
+    assert_same(&Newtype(3));
+    assert_same(&Tuple(1, "two"));
+    assert_same(&Cat {
+        name: "mittens",
+        favourite_toys: vec!["mouse", "box"],
+        owner: None,
+        illness: None,
+        extra: BTreeMap::from([("lives", 9)]),
+    });
+
+    assert_same(&[
+        Mood::Sleepy,
+        Mood::Hungry(3),
+        Mood::Grumpy(10, true),
+        Mood::Playful { toy: "ball" },
+    ]);
+    assert_same(&Internal::Ball { bounciness: 0.5 });
+    assert_same(&Adjacent::Mouse(2));
+    assert_same(&[Untagged::Number(-1), Untagged::Text("x")]);
+}
+
+#[test]
+fn collections() {
+    assert_same(&Vec::<u8>::new());
+    assert_same(&(1, "two", [3.5]));
+    assert_same(&serde_bytes_like());
+    assert_same(&BTreeMap::<u8, u8>::new());
+    assert_same(&BTreeMap::from([("b", vec![1]), ("a", vec![])]));
+    assert_same(&BTreeMap::from([(-1i64, "neg"), (2, "pos")]));
+    assert_same(&BTreeMap::from([(u128::MAX, 1)]));
+    assert_same(&BTreeMap::from([(true, 1), (false, 0)]));
+    assert_same(&BTreeMap::from([('"', 1)]));
+    assert_same(&BTreeMap::from([(Newtype(1), 1)]));
+}
+
+// serialized through serialize_bytes, like serde_bytes would
+fn serde_bytes_like() -> impl Serialize {
+    struct Bytes;
+
+    impl Serialize for Bytes {
+        fn serialize<S: serde::Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+            ser.serialize_bytes(&[0, 1, 255])
+        }
+    }
+
+    Bytes
+}
+
+#[derive(Serialize, PartialEq, Eq, PartialOrd, Ord)]
+enum KeyKind {
+    Plain,
+}
+
+#[test]
+fn unit_variant_keys() {
+    assert_same(&BTreeMap::from([(KeyKind::Plain, 1)]));
+}
+
+#[test]
+fn non_string_keys_are_errors() {
+    let map = BTreeMap::from([(vec![1u8], 1), (vec![2u8], 2)]);
+    assert!(serde_json::to_string(&map).is_err());
+
+    let mut out = String::new();
+    let mut ser = Serializer::new(&mut out);
+    let mut arr = ser.array();
+    let result = arr.add_complex(|ser| map.serialize(ser));
+    arr.add(1);
+    arr.end();
+
+    assert_eq!(result.unwrap_err().to_string(), "key must be a string");
+    // the failed map is cut short, but the document around it is still whole
+    assert_eq!(parse(&out), serde_json::json!([{ "": null }, 1]));
+}
+
+#[test]
+fn non_finite_float_keys_are_errors() {
+    struct NanKey;
+
+    impl Serialize for NanKey {
+        fn serialize<S: serde::Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+            ser.collect_map([(f64::NAN, 1)])
+        }
+    }
+
+    let mut out = String::new();
+    let result = NanKey.serialize(SingleValueSerializer::new(&mut out));
+
+    assert!(result.is_err());
+    parse(&out);
+}
+
+#[test]
+fn errors_are_reported() {
+    let map = BTreeMap::from([(vec![1u8], 1)]);
+    assert_eq!(nyoom(Serde(&map)), r#"{"":null}"#);
+
+    let mut error = None;
+    let mut out = String::new();
+    let mut ser = Serializer::new(&mut out);
+    let mut arr = ser.array();
+    arr.add(Serde(&map).report_errors(&mut error));
+    arr.add(Serde(&1).report_errors(&mut None));
+    arr.end();
+
+    assert_eq!(parse(&out), serde_json::json!([{ "": null }, 1]));
+    assert_eq!(error.unwrap().to_string(), "key must be a string");
+
+    let mut error = None;
+    assert_eq!(nyoom(Serde(&[1, 2]).report_errors(&mut error)), "[1,2]");
+    assert!(error.is_none());
+}
@@ -0,0 +1,170 @@
+#![cfg(feature = "sval")]
+
+mod common;
+
+use std::collections::BTreeMap;
+
+use common::{nyoom, parse};
+use nyoom_json::{Serializer, Sval};
+use sval::{Label, Stream, Value};
+
+fn assert_same<T: Value>(value: T) {
+    assert_eq!(
+        nyoom(Sval(&value)),
+        sval_json::stream_to_string(&value).unwrap()
+    );
+}
+
+struct Cat;
+
+impl Value for Cat {
+    fn stream<'sval, S: Stream<'sval> + ?Sized>(&'sval self, stream: &mut S) -> sval::Result {
+        stream.record_begin(None, Some(&Label::new("Cat")), None, Some(2))?;
+        stream.record_value_begin(None, &Label::new("name"))?;
+        stream.value("mittens \"the cat\"")?;
+        stream.record_value_end(None, &Label::new("name"))?;
+        stream.record_value_begin(None, &Label::new("lives"))?;
+        stream.value(&9u8)?;
+        stream.record_value_end(None, &Label::new("lives"))?;
+        stream.record_end(None, Some(&Label::new("Cat")), None)
+    }
+}
+
+#[test]
+fn matches_sval_json() {
+    assert_same(true);
+    assert_same(-5i64);
+    assert_same(u128::MAX);
+    assert_same(1.5f64);
+    assert_same("escapes \n and \u{1}");
+    assert_same(None::<u8>);
+    assert_same(Some(3));
+    assert_same([1, 2, 3]);
+    assert_same((1, "two", 3.5));
+    assert_same(BTreeMap::from([("b", 1), ("a", 2)]));
+    assert_same(BTreeMap::from([(1, "one"), (2, "two")]));
+    assert_same(BTreeMap::from([(true, [Some(1.5), None])]));
+    assert_same(Cat);
+    assert_same([Cat, Cat]);
+}
+
+/// A map whose only key is a sequence, which can't be written as a string.
+struct SeqKey;
+
+impl Value for SeqKey {
+    fn stream<'sval, S: Stream<'sval> + ?Sized>(&'sval self, stream: &mut S) -> sval::Result {
+        stream.map_begin(Some(1))?;
+        stream.map_key_begin()?;
+        stream.seq_begin(Some(1))?;
+        stream.seq_value_begin()?;
+        stream.i32(1)?;
+        stream.seq_value_end()?;
+        stream.seq_end()?;
+        stream.map_key_end()?;
+        stream.map_value_begin()?;
+        stream.i32(1)?;
+        stream.map_value_end()?;
+        stream.map_end()
+    }
+}
+
+/// Fails partway through whatever `stage` says, after writing a few values.
+struct FailsAt(u8);
+
+impl Value for FailsAt {
+    fn stream<'sval, S: Stream<'sval> + ?Sized>(&'sval self, stream: &mut S) -> sval::Result {
+        let fail = |stage| match self.0 == stage {
+            true => Err(sval::Error::new()),
+            false => Ok(()),
+        };
+
+        fail(0)?;
+        stream.seq_begin(None)?;
+        stream.seq_value_begin()?;
+        fail(1)?;
+        stream.map_begin(None)?;
+        stream.map_key_begin()?;
+        stream.text_begin(None)?;
+        stream.text_fragment("ke")?;
+        fail(2)?;
+        stream.text_fragment("y")?;
+        stream.text_end()?;
+        stream.map_key_end()?;
+        fail(3)?;
+        stream.map_value_begin()?;
+        stream.text_begin(None)?;
+        stream.text_fragment("val")?;
+        fail(4)?;
+        stream.text_end()?;
+        stream.map_value_end()?;
+        fail(5)?;
+        stream.map_end()?;
+        stream.seq_value_end()?;
+        stream.seq_end()
+    }
+}
+
+struct Nested(usize);
+
+impl Value for Nested {
+    fn stream<'sval, S: Stream<'sval> + ?Sized>(&'sval self, stream: &mut S) -> sval::Result {
+        for _ in 0..self.0 {
+            stream.seq_begin(Some(1))?;
+            stream.seq_value_begin()?;
+        }
+        stream.null()?;
+        for _ in 0..self.0 {
+            stream.seq_value_end()?;
+            stream.seq_end()?;
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn failed_stream_leaves_valid_json() {
+    assert_eq!(nyoom(Sval(SeqKey)), r#"{"":null}"#);
+
+    let mut out = String::new();
+    let mut ser = Serializer::new(&mut out);
+    let mut arr = ser.array();
+    arr.add(Sval(SeqKey));
+    arr.add(1);
+    arr.end();
+    assert_eq!(parse(&out), serde_json::json!([{ "": null }, 1]));
+
+    let expected = [
+        serde_json::json!(null),
+        serde_json::json!([null]),
+        serde_json::json!([{ "ke": null }]),
+        serde_json::json!([{ "key": null }]),
+        serde_json::json!([{ "key": "val" }]),
+        serde_json::json!([{ "key": "val" }]),
+    ];
+    for (stage, expected) in expected.into_iter().enumerate() {
+        let mut out = String::new();
+        let mut ser = Serializer::new(&mut out);
+        let mut obj = ser.object();
+        obj.field("before", 1);
+        obj.field("value", Sval(FailsAt(stage as u8)));
+        obj.field("after", 2);
+        obj.end();
+
+        assert_eq!(
+            parse(&out),
+            serde_json::json!({ "before": 1, "value": expected, "after": 2 }),
+            "failing at stage {stage}"
+        );
+    }
+}
+
+#[test]
+fn deep_nesting_is_cut_off() {
+    assert_same(Nested(128));
+
+    // serde_json can't parse anything this deep, so check the text directly
+    assert_eq!(
+        nyoom(Sval(Nested(200))),
+        format!("{}null{}", "[".repeat(128), "]".repeat(128))
+    );
+}
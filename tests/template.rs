@@ -0,0 +1,156 @@
+mod common;
+
+use common::{nyoom, parse};
+use nyoom_json::{template, Serializer};
+use serde_json::json;
+
+template! {
+    struct Whole = (value: u8)
+}
+
+template! {
+    struct Nested<'a> = {
+        "first": (first: i32),
+        "list": [(a: bool), (b: Option<u8>), [[(deep: &'a str)]], {}],
+        "obj": { "inner": { "x": (x: f64) } },
+        "last": (last: Vec<u8>)
+    }
+}
+
+template! {
+    struct NotHoles<'a> = {
+        "(looks like a hole)": "(so does this: &str)",
+        "escaped \" (quote)": (real: &'a str),
+        "parens": ["(", ")", (after: u8)]
+    }
+}
+
+template! {
+    struct Adjacent = [(a: u8),(b: u8),(c: u8)]
+}
+
+// wider than the recursion limit would allow if every token took a step
+template! {
+    struct Wide = {
+        "n0": 0, "h1": (h1: u8), "z2": null, "a3": [(a3: u8), "x"], "n4": 4, "h5": (h5: u8),
+        "z6": null, "a7": [(a7: u8), "x"], "n8": 8, "h9": (h9: u8), "z10": null,
+        "a11": [(a11: u8), "x"], "n12": 12, "h13": (h13: u8), "z14": null, "a15": [(a15: u8), "x"],
+        "n16": 16, "h17": (h17: u8), "z18": null, "a19": [(a19: u8), "x"], "n20": 20,
+        "h21": (h21: u8), "z22": null, "a23": [(a23: u8), "x"], "n24": 24, "h25": (h25: u8),
+        "z26": null, "a27": [(a27: u8), "x"], "n28": 28, "h29": (h29: u8), "z30": null,
+        "a31": [(a31: u8), "x"], "n32": 32, "h33": (h33: u8), "z34": null, "a35": [(a35: u8), "x"],
+        "n36": 36, "h37": (h37: u8), "z38": null, "a39": [(a39: u8), "x"], "n40": 40,
+        "h41": (h41: u8), "z42": null, "a43": [(a43: u8), "x"], "n44": 44, "h45": (h45: u8),
+        "z46": null, "a47": [(a47: u8), "x"], "n48": 48, "h49": (h49: u8), "z50": null,
+        "a51": [(a51: u8), "x"], "n52": 52, "h53": (h53: u8), "z54": null, "a55": [(a55: u8), "x"],
+        "n56": 56, "h57": (h57: u8), "z58": null, "a59": [(a59: u8), "x"], "n60": 60,
+        "h61": (h61: u8), "z62": null, "a63": [(a63: u8), "x"]
+    }
+}
+
+#[test]
+fn whole_document_hole() {
+    assert_eq!(nyoom(Whole { value: 7 }), "7");
+}
+
+#[test]
+fn holes_in_nested_positions() {
+    let out = nyoom(Nested {
+        first: -1,
+        a: true,
+        b: None,
+        deep: "\"quoted\"",
+        x: 0.5,
+        last: vec![1, 2],
+    });
+
+    assert_eq!(
+        parse(&out),
+        json!({
+            "first": -1,
+            "list": [true, null, [["\"quoted\""]], {}],
+            "obj": { "inner": { "x": 0.5 } },
+            "last": [1, 2]
+        })
+    );
+}
+
+#[test]
+fn parentheses_in_strings_are_not_holes() {
+    let out = nyoom(NotHoles {
+        real: "mittens",
+        after: 3,
+    });
+
+    assert_eq!(
+        out,
+        r#"{"(looks like a hole)":"(so does this: &str)","escaped \" (quote)":"mittens","parens":["(",")",3]}"#
+    );
+}
+
+#[test]
+fn adjacent_holes() {
+    assert_eq!(nyoom(Adjacent { a: 1, b: 2, c: 3 }), "[1,2,3]");
+}
+
+#[test]
+fn by_reference() {
+    let value = Adjacent { a: 4, b: 5, c: 6 };
+
+    let mut out = String::new();
+    let mut ser = Serializer::new(&mut out);
+    let mut arr = ser.array();
+    arr.add(&value);
+    arr.add(&value);
+    arr.end();
+
+    assert_eq!(parse(&out), json!([[4, 5, 6], [4, 5, 6]]));
+}
+
+#[test]
+fn wide_templates() {
+    let out = nyoom(Wide {
+        h1: 1,
+        a3: 3,
+        h5: 5,
+        a7: 7,
+        h9: 9,
+        a11: 11,
+        h13: 13,
+        a15: 15,
+        h17: 17,
+        a19: 19,
+        h21: 21,
+        a23: 23,
+        h25: 25,
+        a27: 27,
+        h29: 29,
+        a31: 31,
+        h33: 33,
+        a35: 35,
+        h37: 37,
+        a39: 39,
+        h41: 41,
+        a43: 43,
+        h45: 45,
+        a47: 47,
+        h49: 49,
+        a51: 51,
+        h53: 53,
+        a55: 55,
+        h57: 57,
+        a59: 59,
+        h61: 61,
+        a63: 63,
+    });
+
+    let expected: serde_json::Map<_, _> = (0..64)
+        .map(|i| match i % 4 {
+            0 => (format!("n{i}"), json!(i)),
+            1 => (format!("h{i}"), json!(i)),
+            2 => (format!("z{i}"), json!(null)),
+            _ => (format!("a{i}"), json!([i, "x"])),
+        })
+        .collect();
+    assert_eq!(parse(&out), serde_json::Value::Object(expected));
+}
@@ -0,0 +1,121 @@
+#![cfg(feature = "std")]
+
+mod common;
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use common::nyoom;
+use nyoom_json::{DurationAs, DurationFormat, TimestampAs, TimestampFormat};
+
+/// Times on either side of the epoch, with fractions that need 0, 3, 6 and 9 digits.
+fn times() -> Vec<SystemTime> {
+    let mut times = vec![];
+    for secs in [
+        0,
+        1,
+        59,
+        86_399,
+        86_400,
+        951_782_400,
+        1_680_352_200,
+        4_102_444_800,
+    ] {
+        for nanos in [
+            0,
+            1,
+            999_999_999,
+            250_000_000,
+            500_000,
+            120_000,
+            123_456_789,
+        ] {
+            let offset = Duration::new(secs, nanos);
+            times.push(UNIX_EPOCH + offset);
+            times.push(UNIX_EPOCH - offset);
+        }
+    }
+    times
+}
+
+#[test]
+fn rfc3339_matches_chrono() {
+    for time in times() {
+        let expected = DateTime::<Utc>::from(time).to_rfc3339_opts(SecondsFormat::AutoSi, true);
+        assert_eq!(nyoom(time), format!("\"{expected}\""), "{time:?}");
+    }
+}
+
+#[test]
+fn pre_epoch() {
+    let before = UNIX_EPOCH - Duration::from_millis(1);
+    assert_eq!(nyoom(before), r#""1969-12-31T23:59:59.999Z""#);
+    assert_eq!(
+        nyoom(TimestampAs(before, TimestampFormat::UnixMilliseconds)),
+        "-1"
+    );
+    assert_eq!(
+        nyoom(TimestampAs(before, TimestampFormat::UnixSeconds)),
+        "-0.001"
+    );
+
+    let before = UNIX_EPOCH - Duration::new(1, 500_000_000);
+    assert_eq!(nyoom(before), r#""1969-12-31T23:59:58.500Z""#);
+    assert_eq!(
+        nyoom(TimestampAs(before, TimestampFormat::UnixSeconds)),
+        "-1.5"
+    );
+    assert_eq!(
+        nyoom(TimestampAs(before, TimestampFormat::UnixMilliseconds)),
+        "-1500"
+    );
+}
+
+#[test]
+fn unix_timestamps_match_chrono() {
+    for time in times() {
+        let chrono = DateTime::<Utc>::from(time);
+        assert_eq!(
+            nyoom(TimestampAs(time, TimestampFormat::UnixMilliseconds)),
+            chrono.timestamp_millis().to_string(),
+            "{time:?}"
+        );
+
+        let secs: f64 = nyoom(TimestampAs(time, TimestampFormat::UnixSeconds))
+            .parse()
+            .unwrap();
+        let expected = chrono.timestamp() as f64 + chrono.timestamp_subsec_nanos() as f64 / 1e9;
+        assert!(
+            (secs - expected).abs() < 1e-6,
+            "{time:?}: {secs} != {expected}"
+        );
+    }
+}
+
+#[test]
+fn durations() {
+    let cases = [
+        (Duration::ZERO, "0.0", "0", r#""PT0S""#),
+        (Duration::from_millis(250), "0.25", "250", r#""PT0.25S""#),
+        (Duration::from_secs(60), "60.0", "60000", r#""PT1M""#),
+        (Duration::from_secs(3600), "3600.0", "3600000", r#""PT1H""#),
+        (
+            Duration::new(90_061, 5),
+            "90061.000000005",
+            "90061000",
+            r#""PT25H1M1.000000005S""#,
+        ),
+    ];
+
+    for (duration, seconds, millis, iso) in cases {
+        assert_eq!(
+            nyoom(DurationAs(duration, DurationFormat::Seconds)),
+            seconds
+        );
+        assert_eq!(
+            nyoom(DurationAs(duration, DurationFormat::Milliseconds)),
+            millis
+        );
+        assert_eq!(nyoom(DurationAs(duration, DurationFormat::Iso8601)), iso);
+    }
+}
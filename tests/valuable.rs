@@ -0,0 +1,153 @@
+#![cfg(feature = "valuable")]
+
+mod common;
+
+use std::collections::BTreeMap;
+
+use common::nyoom;
+use nyoom_json::Valuable;
+use serde::Serialize;
+use valuable::{
+    EnumDef, Enumerable, Fields, NamedField, NamedValues, StructDef, Structable, Value, Variant,
+    VariantDef, Visit,
+};
+
+/// Checks the visited output against serde_json's, for types implementing both the same way.
+fn assert_same<T: valuable::Valuable + Serialize>(value: &T) {
+    assert_eq!(
+        nyoom(Valuable(value)),
+        serde_json::to_string(value).unwrap()
+    );
+}
+
+#[derive(Serialize)]
+enum Mood {
+    Sleepy,
+    Hungry(u8),
+    Grumpy(u8, bool),
+    Playful { toy: &'static str },
+}
+
+static PLAYFUL_FIELDS: &[NamedField<'static>] = &[NamedField::new("toy")];
+
+static MOOD_VARIANTS: &[VariantDef<'static>] = &[
+    VariantDef::new("Sleepy", Fields::Unnamed(0)),
+    VariantDef::new("Hungry", Fields::Unnamed(1)),
+    VariantDef::new("Grumpy", Fields::Unnamed(2)),
+    VariantDef::new("Playful", Fields::Named(PLAYFUL_FIELDS)),
+];
+
+impl valuable::Valuable for Mood {
+    fn as_value(&self) -> Value<'_> {
+        Value::Enumerable(self)
+    }
+
+    fn visit(&self, visit: &mut dyn Visit) {
+        match self {
+            Mood::Sleepy => visit.visit_unnamed_fields(&[]),
+            Mood::Hungry(hunger) => visit.visit_unnamed_fields(&[hunger.as_value()]),
+            Mood::Grumpy(grumpiness, hissing) => {
+                visit.visit_unnamed_fields(&[grumpiness.as_value(), hissing.as_value()])
+            }
+            Mood::Playful { toy } => {
+                visit.visit_named_fields(&NamedValues::new(PLAYFUL_FIELDS, &[toy.as_value()]))
+            }
+        }
+    }
+}
+
+impl Enumerable for Mood {
+    fn definition(&self) -> EnumDef<'_> {
+        EnumDef::new_static("Mood", MOOD_VARIANTS)
+    }
+
+    fn variant(&self) -> Variant<'_> {
+        let i = match self {
+            Mood::Sleepy => 0,
+            Mood::Hungry(_) => 1,
+            Mood::Grumpy(..) => 2,
+            Mood::Playful { .. } => 3,
+        };
+        Variant::Static(&MOOD_VARIANTS[i])
+    }
+}
+
+#[derive(Serialize)]
+struct Unit;
+
+#[derive(Serialize)]
+struct Newtype(u8);
+
+#[derive(Serialize)]
+struct Pair(&'static str, Option<u8>);
+
+macro_rules! unnamed_struct {
+    ($($ty:ident($($field:tt),*);)*) => {
+        $(
+            impl valuable::Valuable for $ty {
+                fn as_value(&self) -> Value<'_> {
+                    Value::Structable(self)
+                }
+
+                #[allow(unused_variables)]
+                fn visit(&self, visit: &mut dyn Visit) {
+                    visit.visit_unnamed_fields(&[$(self.$field.as_value()),*]);
+                }
+            }
+
+            impl Structable for $ty {
+                fn definition(&self) -> StructDef<'_> {
+                    StructDef::new_static(
+                        stringify!($ty),
+                        Fields::Unnamed(<[&str]>::len(&[$(stringify!($field)),*])),
+                    )
+                }
+            }
+        )*
+    };
+}
+
+unnamed_struct! {
+    Unit();
+    Newtype(0);
+    Pair(0, 1);
+}
+
+#[test]
+fn enums() {
+    assert_same(&Mood::Sleepy);
+    assert_same(&Mood::Hungry(3));
+    assert_same(&Mood::Grumpy(10, true));
+    assert_same(&Mood::Playful { toy: "ball" });
+    assert_same(&vec![Mood::Sleepy, Mood::Hungry(1), Mood::Sleepy]);
+}
+
+#[test]
+fn unnamed_structs() {
+    assert_same(&Unit);
+    assert_same(&Newtype(7));
+    assert_same(&Pair("mittens", None));
+    assert_same(&vec![Pair("beans", Some(9))]);
+}
+
+#[test]
+fn maps() {
+    assert_same(&BTreeMap::<&str, u8>::new());
+    assert_same(&BTreeMap::from([("b", vec![1]), ("a", vec![])]));
+    assert_same(&BTreeMap::from([(-1i64, "neg"), (2, "pos")]));
+    assert_same(&BTreeMap::from([('"', 1)]));
+    assert_same(&BTreeMap::from([(
+        "nested",
+        BTreeMap::from([("deep", 1.5)]),
+    )]));
+}
+
+#[test]
+fn tuples_and_lists() {
+    assert_same(&());
+    assert_same(&(1, "two", 3.5));
+    assert_same(&((), (true,), [None, Some(-1)]));
+    assert_same(&vec![(1, 'a'), (2, 'b')]);
+    assert_same(&[0u8, 255]);
+    assert_same(&Vec::<u8>::new());
+}